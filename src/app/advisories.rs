@@ -0,0 +1,854 @@
+use std::{collections::HashSet, fmt};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{
+  de::{DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor},
+  Serialize,
+};
+use serde_json::Value;
+
+use super::{
+  certificate::{date, Certificate},
+  config::{Config, Severity},
+  jwt_decoder::Payload,
+  summary::{issuer_mismatch, missing_audiences},
+  utils::{B64_PREFIX, HEX_PREFIX, JWKS_PREFIX},
+};
+
+/// inline secrets shorter than this aren't suspected to be encoded, short words decode as base64
+const MIN_ENCODED_SECRET_LENGTH: usize = 16;
+
+/// A non blocking warning about the decoded token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Advisory {
+  pub code: &'static str,
+  pub message: String,
+}
+
+impl Advisory {
+  fn new(code: &'static str, message: String) -> Self {
+    Advisory { code, message }
+  }
+}
+
+/// flags tokens issued in the future, tokens that never expire or only in the far future and tokens
+/// with a suspicious lifetime, per the thresholds of `config`
+pub fn check_lifetime(claims: &Payload, config: &Config, now: i64) -> Vec<Advisory> {
+  let mut advisories = vec![];
+  let iat = claims.timestamp("iat");
+
+  if !claims.0.contains_key("exp") && !config.allow_no_exp {
+    advisories.push(Advisory::new(
+      "no_exp",
+      "No expiry — the token without `exp` never expires".into(),
+    ));
+  }
+
+  if let Some(iat) = iat {
+    if iat > now + config.leeway {
+      advisories.push(Advisory::new(
+        "iat_in_future",
+        format!("`iat` is {}s in the future", iat - now),
+      ));
+    }
+  }
+
+  if let (Some(iat), Some(exp)) = (iat, claims.timestamp("exp")) {
+    let lifetime = exp - iat;
+    if lifetime < config.min_lifetime {
+      advisories.push(Advisory::new(
+        "lifetime_too_short",
+        format!(
+          "Token lifetime of {lifetime}s is shorter than {}s",
+          config.min_lifetime
+        ),
+      ));
+    } else if lifetime > config.max_lifetime {
+      advisories.push(Advisory::new(
+        "lifetime_too_long",
+        format!(
+          "Token lifetime of {lifetime}s is longer than {}s",
+          config.max_lifetime
+        ),
+      ));
+    }
+  }
+
+  if let Some(exp) = claims.timestamp("exp") {
+    if exp - now > config.max_exp_in_future {
+      advisories.push(Advisory::new(
+        "exp_far_future",
+        format!(
+          "`exp` is {}s in the future, more than {}s",
+          exp - now,
+          config.max_exp_in_future
+        ),
+      ));
+    }
+  }
+
+  advisories
+}
+
+/// flags a missing expected audience when it's only a warning per `audience_severity`, as an error
+/// it's in the summary instead
+pub fn check_audience(claims: &Payload, config: &Config) -> Vec<Advisory> {
+  if config.audience_severity != Severity::Warning {
+    return vec![];
+  }
+  let missing = missing_audiences(claims, &config.audiences, config.audience_mode);
+  if config.audiences.is_empty() || missing.is_empty() {
+    return vec![];
+  }
+  vec![Advisory::new(
+    "aud_mismatch",
+    format!("`aud` is missing the audiences [{}]", missing.join(", ")),
+  )]
+}
+
+/// flags a token without a `jti` to check against the `jti_denylist`, replays of it can't be told
+pub fn check_jti(claims: &Payload, config: &Config) -> Vec<Advisory> {
+  if config.jti_denylist.is_none() || claims.0.get("jti").is_some_and(Value::is_string) {
+    return vec![];
+  }
+  vec![Advisory::new(
+    "no_jti",
+    "No `jti` claim to check against the denylist, replays of the token can't be told".into(),
+  )]
+}
+
+/// flags an `iss` claim other than the expected issuer when it's only a warning per
+/// `issuer_severity`
+pub fn check_issuer(claims: &Payload, config: &Config) -> Vec<Advisory> {
+  match &config.issuer {
+    Some(issuer)
+      if config.issuer_severity == Severity::Warning
+        && issuer_mismatch(claims, issuer).is_some() =>
+    {
+      vec![Advisory::new(
+        "iss_mismatch",
+        format!("`iss` is not the expected issuer `{issuer}`"),
+      )]
+    }
+    _ => vec![],
+  }
+}
+
+/// flags an HMAC secret used as raw text that looks hex or base64 encoded, as a secret of another
+/// encoding entered without its prefix fails the verification without telling why
+pub fn check_secret_encoding(secret: &str) -> Vec<Advisory> {
+  if secret.starts_with('@')
+    || secret.starts_with(B64_PREFIX)
+    || secret.starts_with(HEX_PREFIX)
+    || secret.starts_with(JWKS_PREFIX)
+    || secret.len() < MIN_ENCODED_SECRET_LENGTH
+  {
+    return vec![];
+  }
+  let hex = secret.len().is_multiple_of(2) && secret.chars().all(|c| c.is_ascii_hexdigit());
+  // words decode as base64 too, encoded secrets mix in digits or symbols
+  let base64 = STANDARD.decode(secret).is_ok()
+    && secret
+      .chars()
+      .any(|c| c.is_ascii_digit() || "+/=".contains(c));
+  let (encoding, prefix) = match (hex, base64) {
+    (true, _) => ("hex", HEX_PREFIX),
+    (false, true) => ("base64", B64_PREFIX),
+    _ => return vec![],
+  };
+  vec![Advisory::new(
+    "secret_looks_encoded",
+    format!(
+      "Secret is used as raw text but looks {encoding} encoded, prefix it with `{prefix}` if it is"
+    ),
+  )]
+}
+
+/// flags HMAC secrets shorter than the configured minimum for `alg`
+pub fn check_secret_length(alg: &str, secret: &[u8], config: &Config) -> Vec<Advisory> {
+  match config.min_secret_length(alg) {
+    Some(min) if secret.len() < min => vec![Advisory::new(
+      "secret_too_short",
+      format!(
+        "Secret is {} bytes, {min}+ recommended for {alg}",
+        secret.len()
+      ),
+    )],
+    _ => vec![],
+  }
+}
+
+/// flags an expired certificate given as the key, whose use depends on `allow_expired_cert`
+pub fn check_certificate(certificate: &Certificate, config: &Config, now: i64) -> Vec<Advisory> {
+  if !certificate.is_expired(now) {
+    return vec![];
+  }
+  let expired = format!("Certificate expired on {}", date(certificate.not_after));
+  let message = if config.allow_expired_cert {
+    format!("{expired}, used anyway")
+  } else {
+    format!("{expired}, not used unless allowed with --allow-expired-cert")
+  };
+  vec![Advisory::new("cert_expired", message)]
+}
+
+/// flags an `alg` in the raw header JSON that the policy in `config` tags as weak. Verification
+/// isn't affected
+pub fn check_weak_alg(header_json: &[u8], config: &Config) -> Vec<Advisory> {
+  let Ok(Value::Object(header)) = serde_json::from_slice::<Value>(header_json) else {
+    return vec![];
+  };
+  let Some(alg) = header.get("alg").and_then(Value::as_str) else {
+    return vec![];
+  };
+  match config.weak_algs.get(alg) {
+    Some(reason) => vec![Advisory::new(
+      "weak_alg",
+      format!("Weak `alg` {alg}: {reason}"),
+    )],
+    None => vec![],
+  }
+}
+
+/// flags signatures whose length can't be produced by the `alg` in the raw header JSON, a sign of
+/// a token mangled by copy and paste or a mislabeled `alg`. RSA signatures are as long as the key,
+/// so any common key size is accepted
+pub fn check_signature_length(header_json: &[u8], signature: &[u8]) -> Vec<Advisory> {
+  let Ok(Value::Object(header)) = serde_json::from_slice::<Value>(header_json) else {
+    return vec![];
+  };
+  let Some(alg) = header.get("alg").and_then(Value::as_str) else {
+    return vec![];
+  };
+  let expected = match alg {
+    "HS256" => 32..=32,
+    "HS384" => 48..=48,
+    "HS512" => 64..=64,
+    "ES256" | "ES256K" | "EdDSA" => 64..=64,
+    "ES384" => 96..=96,
+    // 1024 to 8192 bit keys
+    "RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512" => 128..=1024,
+    _ => return vec![],
+  };
+  // a missing signature is reported by the decoder
+  if signature.is_empty() || expected.contains(&signature.len()) {
+    return vec![];
+  }
+  let expected = if expected.start() == expected.end() {
+    format!("{}", expected.start())
+  } else {
+    format!("{} to {}", expected.start(), expected.end())
+  };
+  vec![Advisory::new(
+    "signature_length",
+    format!(
+      "Signature is {} bytes, {alg} signatures are {expected} bytes",
+      signature.len()
+    ),
+  )]
+}
+
+/// header parameters registered by RFC 7515 and RFC 7516, which `crit` must not list
+const REGISTERED_HEADER_PARAMS: &[&str] = &[
+  "alg", "jku", "jwk", "kid", "x5u", "x5c", "x5t", "x5t#S256", "typ", "cty", "crit", "enc", "zip",
+];
+
+/// critical extensions jwt-ui implements, `b64` of RFC 7797 unencoded payloads
+const UNDERSTOOD_CRITICAL_PARAMS: &[&str] = &["b64"];
+
+/// flags the critical extensions listed in `crit` of the raw header JSON. A compliant verifier
+/// rejects tokens with critical extensions it doesn't understand. `b64: false` must be listed too,
+/// a verifier unaware of it would read the unencoded payload as base64url
+pub fn check_crit(header_json: &[u8]) -> Vec<Advisory> {
+  let Ok(Value::Object(header)) = serde_json::from_slice::<Value>(header_json) else {
+    return vec![];
+  };
+  let names = header.get("crit").map(|crit| {
+    crit
+      .as_array()
+      .and_then(|names| names.iter().map(Value::as_str).collect::<Option<Vec<_>>>())
+  });
+  if header.get("b64") == Some(&Value::Bool(false))
+    && !names.iter().flatten().flatten().any(|name| *name == "b64")
+  {
+    return vec![Advisory::new(
+      "b64_not_critical",
+      "`b64: false` without `crit` listing `b64` is not RFC 7797 compliant, verifiers unaware of it decode the unencoded payload as base64url"
+        .into(),
+    )];
+  }
+  let names = match names {
+    None => return vec![],
+    Some(Some(names)) if !names.is_empty() => names,
+    _ => {
+      return vec![Advisory::new(
+        "crit_malformed",
+        "`crit` must be a non-empty array of header parameter names".into(),
+      )]
+    }
+  };
+
+  names
+    .into_iter()
+    .filter_map(|name| {
+      if REGISTERED_HEADER_PARAMS.contains(&name) {
+        Some(Advisory::new(
+          "crit_registered",
+          format!("`crit` lists the standard `{name}`, which it must not"),
+        ))
+      } else if !header.contains_key(name) {
+        Some(Advisory::new(
+          "crit_missing",
+          format!("Critical `{name}` is listed in `crit` but missing from the header"),
+        ))
+      } else if UNDERSTOOD_CRITICAL_PARAMS.contains(&name) {
+        None
+      } else {
+        Some(Advisory::new(
+          "crit_unknown",
+          format!("Critical `{name}` is not understood, a compliant verifier rejects the token"),
+        ))
+      }
+    })
+    .collect()
+}
+
+/// flags a header `alg` other than the one pinned for the session, the token is verified with the
+/// pinned one
+pub fn check_pinned_alg(header_json: &[u8], pinned: &str) -> Vec<Advisory> {
+  let Ok(Value::Object(header)) = serde_json::from_slice::<Value>(header_json) else {
+    return vec![];
+  };
+  let alg = header
+    .get("alg")
+    .and_then(Value::as_str)
+    .unwrap_or_default();
+  if alg == pinned {
+    return vec![];
+  }
+  vec![Advisory::new(
+    "alg_not_pinned",
+    format!(
+      "The header `alg` {alg} differs from the pinned {pinned}, it's verified as {pinned}: the token may be forged or its issuer misconfigured"
+    ),
+  )]
+}
+
+/// flags the `jku` and `x5u` headers, URLs of the keys a naive verifier fetches and trusts. They
+/// are shown and never requested, keys are only fetched from `jwks:` URLs the user supplies
+pub fn check_header_urls(header_json: &[u8]) -> Vec<Advisory> {
+  let Ok(Value::Object(header)) = serde_json::from_slice::<Value>(header_json) else {
+    return vec![];
+  };
+  [
+    ("jku", "jku_url", "JWK Set"),
+    ("x5u", "x5u_url", "certificate chain"),
+  ]
+  .into_iter()
+  .filter_map(|(name, code, target)| {
+    let url = match header.get(name)? {
+      Value::String(url) => url.clone(),
+      other => other.to_string(),
+    };
+    Some(Advisory::new(
+      code,
+      format!(
+        "`{name}` points at a {target} at {url}, not fetched: trusting keys from a header URL \
+           lets the token pick its own key and fetching it is an SSRF risk"
+      ),
+    ))
+  })
+  .collect()
+}
+
+/// flags payloads that are valid JSON but not an object, which claim checks like expiry don't
+/// apply to
+pub fn check_payload_object(payload_json: &[u8]) -> Vec<Advisory> {
+  let kind = match serde_json::from_slice::<Value>(payload_json) {
+    Ok(Value::Array(_)) => "an array",
+    Ok(Value::String(_)) => "a string",
+    Ok(Value::Number(_)) => "a number",
+    Ok(Value::Bool(_)) => "a boolean",
+    Ok(Value::Null) => "null",
+    Ok(Value::Object(_)) | Err(_) => return vec![],
+  };
+  vec![Advisory::new(
+    "payload_not_object",
+    format!("The payload is {kind} instead of a JSON object, claim checks like expiry don't apply"),
+  )]
+}
+
+/// flags keys that appear more than once in the same object of a JSON token segment, which regular
+/// parsing silently drops. `segment` names the segment in the message, e.g. "header"
+pub fn check_duplicate_keys(json: &[u8], segment: &str) -> Vec<Advisory> {
+  let mut duplicates = vec![];
+  let mut deserializer = serde_json::Deserializer::from_slice(json);
+  // invalid JSON is reported by the decoder itself
+  let _ = DuplicateKeys {
+    path: String::new(),
+    duplicates: &mut duplicates,
+  }
+  .deserialize(&mut deserializer);
+
+  duplicates
+    .into_iter()
+    .map(|key| {
+      Advisory::new(
+        "duplicate_key",
+        format!("duplicate key '{key}' in {segment}"),
+      )
+    })
+    .collect()
+}
+
+/// walks a JSON document collecting the paths of duplicate object keys
+struct DuplicateKeys<'a> {
+  path: String,
+  duplicates: &'a mut Vec<String>,
+}
+
+impl DuplicateKeys<'_> {
+  fn child(&mut self, path: String) -> DuplicateKeys<'_> {
+    DuplicateKeys {
+      path,
+      duplicates: self.duplicates,
+    }
+  }
+}
+
+impl<'de> DeserializeSeed<'de> for DuplicateKeys<'_> {
+  type Value = ();
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+    deserializer.deserialize_any(self)
+  }
+}
+
+impl<'de> Visitor<'de> for DuplicateKeys<'_> {
+  type Value = ();
+
+  fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "any JSON value")
+  }
+
+  fn visit_bool<E>(self, _: bool) -> Result<(), E> {
+    Ok(())
+  }
+
+  fn visit_i64<E>(self, _: i64) -> Result<(), E> {
+    Ok(())
+  }
+
+  fn visit_u64<E>(self, _: u64) -> Result<(), E> {
+    Ok(())
+  }
+
+  fn visit_f64<E>(self, _: f64) -> Result<(), E> {
+    Ok(())
+  }
+
+  fn visit_str<E>(self, _: &str) -> Result<(), E> {
+    Ok(())
+  }
+
+  fn visit_unit<E>(self) -> Result<(), E> {
+    Ok(())
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<(), A::Error> {
+    let mut index = 0;
+    while seq
+      .next_element_seed(self.child(format!("{}[{index}]", self.path)))?
+      .is_some()
+    {
+      index += 1;
+    }
+    Ok(())
+  }
+
+  fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+    let mut seen = HashSet::new();
+    while let Some(key) = map.next_key::<String>()? {
+      let path = if self.path.is_empty() {
+        key.clone()
+      } else {
+        format!("{}.{key}", self.path)
+      };
+      if !seen.insert(key) {
+        self.duplicates.push(path.clone());
+      }
+      map.next_value_seed(self.child(path))?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn payload(json: &str) -> Payload {
+    serde_json::from_str(json).unwrap()
+  }
+
+  #[test]
+  fn test_check_lifetime() {
+    let now = 1_700_000_000;
+    let advisories = check_lifetime(&payload(r#"{"sub":"1"}"#), &Config::default(), now);
+    assert_eq!(advisories.len(), 1);
+    assert_eq!(advisories[0].code, "no_exp");
+    assert_eq!(
+      advisories[0].message,
+      "No expiry — the token without `exp` never expires"
+    );
+
+    // the other checks with tokens that never expire allowed
+    let config = Config {
+      allow_no_exp: true,
+      ..Config::default()
+    };
+    assert!(check_lifetime(&payload(r#"{"sub":"1"}"#), &config, now).is_empty());
+    assert!(check_lifetime(
+      &payload(r#"{"iat":1700000000,"exp":1700003600}"#),
+      &config,
+      now
+    )
+    .is_empty());
+
+    let advisories = check_lifetime(&payload(r#"{"exp":1900000000}"#), &config, now);
+    assert_eq!(advisories.len(), 1);
+    assert_eq!(advisories[0].code, "exp_far_future");
+    assert_eq!(
+      advisories[0].message,
+      "`exp` is 200000000s in the future, more than 31536000s"
+    );
+    let lenient = Config {
+      max_exp_in_future: 300_000_000,
+      ..config.clone()
+    };
+    assert!(check_lifetime(&payload(r#"{"exp":1900000000}"#), &lenient, now).is_empty());
+
+    let advisories = check_lifetime(&payload(r#"{"iat":1700000600}"#), &config, now);
+    assert_eq!(advisories.len(), 1);
+    assert_eq!(advisories[0].code, "iat_in_future");
+    assert_eq!(advisories[0].message, "`iat` is 600s in the future");
+
+    // within leeway
+    assert!(check_lifetime(&payload(r#"{"iat":1700000030}"#), &config, now).is_empty());
+
+    let advisories = check_lifetime(
+      &payload(r#"{"iat":1700000000,"exp":1700000010}"#),
+      &config,
+      now,
+    );
+    assert_eq!(advisories[0].code, "lifetime_too_short");
+
+    let advisories = check_lifetime(
+      &payload(r#"{"iat":1600000000,"exp":1800000000}"#),
+      &config,
+      now,
+    );
+    assert_eq!(advisories[0].code, "lifetime_too_long");
+    assert_eq!(
+      advisories[0].message,
+      "Token lifetime of 200000000s is longer than 86400s"
+    );
+  }
+
+  #[test]
+  fn test_check_audience() {
+    let claims = payload(r#"{"aud":"api"}"#);
+    let config = Config {
+      audiences: vec!["web".into()],
+      ..Config::default()
+    };
+    // an error is in the summary
+    assert!(check_audience(&claims, &config).is_empty());
+
+    let config = Config {
+      audience_severity: Severity::Warning,
+      ..config
+    };
+    let advisories = check_audience(&claims, &config);
+    assert_eq!(advisories.len(), 1);
+    assert_eq!(advisories[0].code, "aud_mismatch");
+    assert_eq!(
+      advisories[0].message,
+      "`aud` is missing the audiences [web]"
+    );
+
+    let config = Config {
+      audiences: vec!["api".into()],
+      ..config
+    };
+    assert!(check_audience(&claims, &config).is_empty());
+  }
+
+  #[test]
+  fn test_check_issuer() {
+    let claims = payload(r#"{"iss":"https://staging"}"#);
+    let config = Config {
+      issuer: Some("https://prod".into()),
+      issuer_severity: Severity::Warning,
+      ..Config::default()
+    };
+    let advisories = check_issuer(&claims, &config);
+    assert_eq!(advisories[0].code, "iss_mismatch");
+    assert_eq!(
+      advisories[0].message,
+      "`iss` is not the expected issuer `https://prod`"
+    );
+
+    let config = Config {
+      issuer_severity: Severity::Error,
+      ..config
+    };
+    assert!(check_issuer(&claims, &config).is_empty());
+  }
+
+  #[test]
+  fn test_check_jti() {
+    let config = Config {
+      jti_denylist: Some(["a1".to_string()].into()),
+      ..Config::default()
+    };
+    assert_eq!(check_jti(&payload("{}"), &config)[0].code, "no_jti");
+    assert!(check_jti(&payload(r#"{"jti":"a1"}"#), &config).is_empty());
+    assert!(check_jti(&payload("{}"), &Config::default()).is_empty());
+  }
+
+  #[test]
+  fn test_check_lifetime_with_utc_dates() {
+    let mut claims = payload(r#"{"iat":1700000000,"exp":1700000010}"#);
+    claims.convert_timestamps();
+
+    let advisories = check_lifetime(&claims, &Config::default(), 1_700_000_000);
+    assert_eq!(advisories[0].code, "lifetime_too_short");
+  }
+
+  #[test]
+  fn test_check_secret_encoding() {
+    let advisories = check_secret_encoding("c2VjcmV0c2VjcmV0c2VjcmV0");
+    assert_eq!(advisories.len(), 1);
+    assert_eq!(advisories[0].code, "secret_looks_encoded");
+    assert_eq!(
+      advisories[0].message,
+      "Secret is used as raw text but looks base64 encoded, prefix it with `b64:` if it is"
+    );
+    assert!(check_secret_encoding("736563726574736563726574")[0]
+      .message
+      .contains("looks hex encoded, prefix it with `hex:`"));
+
+    for secret in [
+      "b64:c2VjcmV0c2VjcmV0c2VjcmV0",
+      "hex:736563726574736563726574",
+      "@./test_data/test_hmac_secrets.txt",
+      "your-256-bit-secret",
+      // a word decodes as base64 too
+      "mysupersecretkey",
+      "c2VjcmV0",
+    ] {
+      assert!(check_secret_encoding(secret).is_empty(), "{secret}");
+    }
+  }
+
+  #[test]
+  fn test_check_secret_length() {
+    let mut config = Config::default();
+
+    assert!(check_secret_length("HS256", &[0; 32], &config).is_empty());
+    assert!(check_secret_length("RS256", b"short", &config).is_empty());
+
+    let advisories = check_secret_length("HS512", &[0; 32], &config);
+    assert_eq!(advisories.len(), 1);
+    assert_eq!(advisories[0].code, "secret_too_short");
+    assert_eq!(
+      advisories[0].message,
+      "Secret is 32 bytes, 64+ recommended for HS512"
+    );
+
+    config.min_secret_lengths.insert("HS512".into(), 16);
+    assert!(check_secret_length("HS512", &[0; 32], &config).is_empty());
+  }
+
+  #[test]
+  fn test_check_payload_object() {
+    assert!(check_payload_object(br#"{"sub":"1"}"#).is_empty());
+    assert!(check_payload_object(b"not json").is_empty());
+
+    let advisories = check_payload_object(br#"["a","b"]"#);
+    assert_eq!(advisories[0].code, "payload_not_object");
+    assert_eq!(
+      advisories[0].message,
+      "The payload is an array instead of a JSON object, claim checks like expiry don't apply"
+    );
+    assert!(check_payload_object(br#""hello""#)[0]
+      .message
+      .starts_with("The payload is a string "));
+  }
+
+  #[test]
+  fn test_check_weak_alg() {
+    let config = Config::default();
+    assert!(check_weak_alg(br#"{"alg":"RS256"}"#, &config).is_empty());
+    assert_eq!(
+      check_weak_alg(br#"{"alg":"RS1"}"#, &config),
+      vec![Advisory::new(
+        "weak_alg",
+        "Weak `alg` RS1: RSA with SHA-1 is deprecated, SHA-1 is broken".into()
+      )]
+    );
+    assert_eq!(
+      check_weak_alg(br#"{"alg":"none"}"#, &config)[0].code,
+      "weak_alg"
+    );
+
+    // the policy is configurable
+    let mut config = Config::default();
+    config.weak_algs.remove("none");
+    config
+      .weak_algs
+      .insert("HS256".into(), "not allowed here".into());
+    assert!(check_weak_alg(br#"{"alg":"none"}"#, &config).is_empty());
+    assert_eq!(
+      check_weak_alg(br#"{"alg":"HS256"}"#, &config)[0].message,
+      "Weak `alg` HS256: not allowed here"
+    );
+  }
+
+  #[test]
+  fn test_check_signature_length() {
+    assert!(check_signature_length(br#"{"alg":"HS256"}"#, &[0; 32]).is_empty());
+    assert!(check_signature_length(br#"{"alg":"RS256"}"#, &[0; 256]).is_empty());
+    assert!(check_signature_length(br#"{"alg":"HS256"}"#, &[]).is_empty());
+    assert!(check_signature_length(br#"{"alg":"XY1"}"#, &[0; 3]).is_empty());
+
+    assert_eq!(
+      check_signature_length(br#"{"alg":"HS256"}"#, &[0; 256]),
+      vec![Advisory::new(
+        "signature_length",
+        "Signature is 256 bytes, HS256 signatures are 32 bytes".into()
+      )]
+    );
+    assert_eq!(
+      check_signature_length(br#"{"alg":"PS384"}"#, &[0; 64])[0].message,
+      "Signature is 64 bytes, PS384 signatures are 128 to 1024 bytes"
+    );
+  }
+
+  #[test]
+  fn test_check_header_urls() {
+    assert!(check_header_urls(br#"{"alg":"RS256","kid":"1"}"#).is_empty());
+    assert!(check_header_urls(b"not json").is_empty());
+
+    let advisories = check_header_urls(
+      br#"{"alg":"RS256","x5u":"https://example.com/chain.pem","jku":"http://169.254.169.254/keys"}"#,
+    );
+    assert_eq!(
+      advisories
+        .iter()
+        .map(|a| (a.code, a.message.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          "jku_url",
+          "`jku` points at a JWK Set at http://169.254.169.254/keys, not fetched: trusting keys \
+           from a header URL lets the token pick its own key and fetching it is an SSRF risk"
+        ),
+        (
+          "x5u_url",
+          "`x5u` points at a certificate chain at https://example.com/chain.pem, not fetched: \
+           trusting keys from a header URL lets the token pick its own key and fetching it is an \
+           SSRF risk"
+        ),
+      ]
+    );
+    assert!(check_header_urls(br#"{"jku":1}"#)[0]
+      .message
+      .starts_with("`jku` points at a JWK Set at 1,"));
+  }
+
+  #[test]
+  fn test_check_pinned_alg() {
+    assert!(check_pinned_alg(br#"{"alg":"RS256"}"#, "RS256").is_empty());
+    let advisories = check_pinned_alg(br#"{"alg":"HS256"}"#, "RS256");
+    assert_eq!(advisories[0].code, "alg_not_pinned");
+    assert!(advisories[0]
+      .message
+      .starts_with("The header `alg` HS256 differs from the pinned RS256,"));
+  }
+
+  #[test]
+  fn test_check_crit() {
+    assert!(check_crit(br#"{"alg":"HS256"}"#).is_empty());
+
+    let advisories = check_crit(br#"{"alg":"HS256","crit":["ext","exp","kid"],"ext":1}"#);
+    assert_eq!(
+      advisories
+        .iter()
+        .map(|a| (a.code, a.message.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          "crit_unknown",
+          "Critical `ext` is not understood, a compliant verifier rejects the token"
+        ),
+        (
+          "crit_missing",
+          "Critical `exp` is listed in `crit` but missing from the header"
+        ),
+        (
+          "crit_registered",
+          "`crit` lists the standard `kid`, which it must not"
+        ),
+      ]
+    );
+
+    for crit in [r#""b64""#, "[]", "[1]"] {
+      let header = format!(r#"{{"alg":"HS256","crit":{crit}}}"#);
+      let advisories = check_crit(header.as_bytes());
+      assert_eq!(advisories.len(), 1);
+      assert_eq!(advisories[0].code, "crit_malformed");
+    }
+
+    // `b64` of RFC 7797 is understood, but must be listed when it's false
+    assert!(check_crit(br#"{"alg":"HS256","crit":["b64"],"b64":false}"#).is_empty());
+    for header in [
+      r#"{"alg":"HS256","b64":false}"#,
+      r#"{"alg":"HS256","crit":["ext"],"ext":1,"b64":false}"#,
+    ] {
+      let advisories = check_crit(header.as_bytes());
+      assert_eq!(advisories.len(), 1);
+      assert_eq!(advisories[0].code, "b64_not_critical");
+    }
+    assert!(check_crit(br#"{"alg":"HS256","b64":true}"#).is_empty());
+  }
+
+  #[test]
+  fn test_check_duplicate_keys() {
+    assert!(check_duplicate_keys(br#"{"alg":"HS256","typ":"JWT"}"#, "header").is_empty());
+
+    let advisories = check_duplicate_keys(br#"{"alg":"none","typ":"JWT","alg":"HS256"}"#, "header");
+    assert_eq!(advisories.len(), 1);
+    assert_eq!(advisories[0].code, "duplicate_key");
+    assert_eq!(advisories[0].message, "duplicate key 'alg' in header");
+
+    // nested objects are checked as well, same keys in different objects are fine
+    let advisories = check_duplicate_keys(
+      br#"{"sub":"1","obj":{"sub":"2","a":1,"a":2},"arr":[{"b":1,"b":2}]}"#,
+      "payload",
+    );
+    assert_eq!(
+      advisories
+        .iter()
+        .map(|a| a.message.as_str())
+        .collect::<Vec<_>>(),
+      vec![
+        "duplicate key 'obj.a' in payload",
+        "duplicate key 'arr[0].b' in payload"
+      ]
+    );
+
+    assert!(check_duplicate_keys(b"not json", "payload").is_empty());
+  }
+}
@@ -0,0 +1,268 @@
+//! Assertions on the claims of a token for `verify --assert`, like `roles contains admin`, so that
+//! a pipeline can gate on a token policy and not only on the signature
+
+use std::{fmt, str::FromStr};
+
+use serde_json::Value;
+
+use super::jwt_decoder::{parse_claim_path, Payload};
+
+/// What an assertion checks the claim for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+  Exists,
+  Equals,
+  /// an element of an array, a substring of a string or a key of an object
+  Contains,
+  GreaterThan,
+  LessThan,
+}
+
+impl Operator {
+  fn parse(op: &str) -> Option<Self> {
+    match op {
+      "exists" => Some(Operator::Exists),
+      "equals" | "==" => Some(Operator::Equals),
+      "contains" => Some(Operator::Contains),
+      ">" => Some(Operator::GreaterThan),
+      "<" => Some(Operator::LessThan),
+      _ => None,
+    }
+  }
+}
+
+/// The value a claim is compared with
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+  /// the time the token is checked at, offset by seconds, like `now+3600`
+  Now(i64),
+  /// a JSON value like `42` or `["a"]`, text that isn't JSON is a string as written
+  Value { value: Value, raw: String },
+}
+
+impl Operand {
+  fn parse(operand: &str) -> Result<Self, String> {
+    let offset = operand
+      .strip_prefix("now")
+      .filter(|o| o.is_empty() || o.starts_with(['+', '-']));
+    if let Some(offset) = offset {
+      let offset = match offset.strip_prefix('+') {
+        _ if offset.is_empty() => Ok(0),
+        Some(seconds) => seconds.parse::<i64>(),
+        None => offset.parse::<i64>(),
+      };
+      return offset
+        .map(Operand::Now)
+        .map_err(|_| format!("`{operand}` is not `now` offset by seconds, like `now+60`"));
+    }
+    Ok(Operand::Value {
+      value: serde_json::from_str(operand).unwrap_or_else(|_| Value::from(operand)),
+      raw: operand.into(),
+    })
+  }
+
+  fn value(&self, now: i64) -> Value {
+    match self {
+      Operand::Now(offset) => Value::from(now + offset),
+      Operand::Value { value, .. } => value.clone(),
+    }
+  }
+
+  /// the operand as text, without the quotes of a JSON string
+  fn text(&self, now: i64) -> String {
+    match self {
+      Operand::Now(_) => self.value(now).to_string(),
+      Operand::Value { value, raw } => value.as_str().unwrap_or(raw).into(),
+    }
+  }
+}
+
+/// An assertion like `sub exists` or `exp > now`: a claim path, an operator and an operand for all
+/// operators but `exists`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assertion {
+  expression: String,
+  path: String,
+  operator: Operator,
+  operand: Option<Operand>,
+}
+
+impl FromStr for Assertion {
+  type Err = String;
+
+  fn from_str(expression: &str) -> Result<Self, Self::Err> {
+    let expression = expression.trim();
+    let invalid = |reason: String| format!("`{expression}` is not a valid assertion: {reason}");
+    let mut parts = expression.splitn(3, char::is_whitespace);
+    let path = parts.next().unwrap_or_default();
+    parse_claim_path(path).map_err(invalid)?;
+    let op = parts.next().unwrap_or_default();
+    let operator = Operator::parse(op).ok_or_else(|| {
+      invalid("the operator is one of `exists`, `equals`, `contains`, `>` and `<`".into())
+    })?;
+    let operand = parts.next().map(str::trim).filter(|o| !o.is_empty());
+    let operand = match (operator, operand) {
+      (Operator::Exists, None) => None,
+      (Operator::Exists, Some(_)) => return Err(invalid("`exists` takes no value".into())),
+      (_, None) => return Err(invalid(format!("`{op}` needs a value"))),
+      (_, Some(operand)) => Some(Operand::parse(operand).map_err(invalid)?),
+    };
+    Ok(Assertion {
+      expression: expression.into(),
+      path: path.into(),
+      operator,
+      operand,
+    })
+  }
+}
+
+impl fmt::Display for Assertion {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.expression)
+  }
+}
+
+impl Assertion {
+  /// checks the claim of the payload at unix time `now`, the error names the expression and the
+  /// claim's actual value
+  pub fn check(&self, payload: &Payload, now: i64) -> Result<(), String> {
+    let actual = payload
+      .get_path(&self.path)
+      .map_err(|_| format!("assertion `{self}` failed: `{}` is missing", self.path))?;
+    let Some(operand) = &self.operand else {
+      return Ok(());
+    };
+    let expected = operand.value(now);
+    let text = operand.text(now);
+    let passed = match self.operator {
+      Operator::Exists => true,
+      Operator::Equals => *actual == expected || actual.as_str() == Some(&text),
+      Operator::Contains => match actual {
+        Value::Array(items) => items
+          .iter()
+          .any(|item| *item == expected || item.as_str() == Some(&text)),
+        Value::String(value) => value.contains(&text),
+        Value::Object(map) => map.contains_key(&text),
+        _ => false,
+      },
+      Operator::GreaterThan | Operator::LessThan => match (actual.as_f64(), expected.as_f64()) {
+        (Some(actual), Some(expected)) if self.operator == Operator::GreaterThan => {
+          actual > expected
+        }
+        (Some(actual), Some(expected)) => actual < expected,
+        _ => false,
+      },
+    };
+    if passed {
+      Ok(())
+    } else {
+      Err(format!(
+        "assertion `{self}` failed: `{}` is {actual}",
+        self.path
+      ))
+    }
+  }
+}
+
+/// the messages of the assertions that fail for the payload, in the order given
+pub fn check_assertions(payload: &Payload, assertions: &[Assertion], now: i64) -> Vec<String> {
+  assertions
+    .iter()
+    .filter_map(|assertion| assertion.check(payload, now).err())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  const NOW: i64 = 1_700_000_000;
+
+  fn payload() -> Payload {
+    Payload(
+      serde_json::from_value(json!({
+        "sub": "1234567890",
+        "roles": ["admin", "user"],
+        "scope": "read write",
+        "exp": NOW + 600,
+        "realm_access": { "level": 2 },
+      }))
+      .unwrap(),
+    )
+  }
+
+  fn check(expression: &str) -> Result<(), String> {
+    expression.parse::<Assertion>()?.check(&payload(), NOW)
+  }
+
+  #[test]
+  fn test_assertions() {
+    for expression in [
+      "sub exists",
+      "sub equals 1234567890",
+      "sub == \"1234567890\"",
+      "roles contains admin",
+      "scope contains write",
+      "realm_access contains level",
+      "realm_access.level > 1",
+      "exp > now",
+      "exp < now+3600",
+      "exp > now-60",
+      "sub contains 456",
+    ] {
+      assert_eq!(check(expression), Ok(()), "{expression}");
+    }
+
+    assert_eq!(
+      check("roles contains owner"),
+      Err(r#"assertion `roles contains owner` failed: `roles` is ["admin","user"]"#.into())
+    );
+    assert_eq!(
+      check("exp > now+3600"),
+      Err("assertion `exp > now+3600` failed: `exp` is 1700000600".into())
+    );
+    assert_eq!(
+      check("email exists"),
+      Err("assertion `email exists` failed: `email` is missing".into())
+    );
+    // only numbers are compared
+    assert!(check("sub > 1").is_err());
+
+    let failures = check_assertions(
+      &payload(),
+      &["sub exists".parse().unwrap(), "aud exists".parse().unwrap()],
+      NOW,
+    );
+    assert_eq!(
+      failures,
+      ["assertion `aud exists` failed: `aud` is missing"]
+    );
+  }
+
+  #[test]
+  fn test_parse_assertion() {
+    assert_eq!(
+      "  scope contains read write "
+        .parse::<Assertion>()
+        .unwrap()
+        .to_string(),
+      "scope contains read write"
+    );
+    assert_eq!(
+      "sub".parse::<Assertion>(),
+      Err("`sub` is not a valid assertion: the operator is one of `exists`, `equals`, `contains`, `>` and `<`".into())
+    );
+    assert_eq!(
+      "sub equals".parse::<Assertion>(),
+      Err("`sub equals` is not a valid assertion: `equals` needs a value".into())
+    );
+    assert_eq!(
+      "sub exists 1".parse::<Assertion>(),
+      Err("`sub exists 1` is not a valid assertion: `exists` takes no value".into())
+    );
+    assert!("exp > now+soon".parse::<Assertion>().is_err());
+    assert!("a..b exists".parse::<Assertion>().is_err());
+  }
+}
@@ -0,0 +1,138 @@
+//! Verifying a batch of tokens with one key for `bench`, to sanity-check the throughput of a key
+//! setup before deploying it
+
+use std::{
+  cmp::Reverse,
+  fmt,
+  time::{Duration, Instant},
+};
+
+use super::{jwt_decoder::extract_token, verify::verify_decoded};
+
+/// Counts and timing of a `bench` run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BenchReport {
+  pub verified: usize,
+  pub failed: usize,
+  pub elapsed: Duration,
+  /// reasons the tokens failed for with how many failed for each, the most frequent first
+  pub failures: Vec<(String, usize)>,
+}
+
+impl BenchReport {
+  pub fn total(&self) -> usize {
+    self.verified + self.failed
+  }
+
+  pub fn tokens_per_second(&self) -> f64 {
+    match self.elapsed.as_secs_f64() {
+      secs if secs > 0.0 => self.total() as f64 / secs,
+      _ => 0.0,
+    }
+  }
+}
+
+impl fmt::Display for BenchReport {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "verified {} of {} tokens, {} failed, in {:.3}s ({:.0} tokens/s)",
+      self.verified,
+      self.total(),
+      self.failed,
+      self.elapsed.as_secs_f64(),
+      self.tokens_per_second()
+    )?;
+    for (reason, count) in &self.failures {
+      write!(f, "\n  {count} × {reason}")?;
+    }
+    Ok(())
+  }
+}
+
+/// verifies each non-blank line of `input` `rounds` times like `verify` does, reading the key for
+/// every token and rejecting those over `max_token_size` bytes. Header lines like `Authorization: Bearer <token>` are read like in `csv`
+pub fn bench(
+  input: &str,
+  secret: &str,
+  leeway: i64,
+  now: i64,
+  rounds: usize,
+  max_token_size: usize,
+) -> BenchReport {
+  let tokens = input
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty())
+    .map(|line| extract_token(line).map_or(line.into(), |e| e.token))
+    .collect::<Vec<String>>();
+
+  let mut report = BenchReport::default();
+  let start = Instant::now();
+  for token in (0..rounds).flat_map(|_| &tokens) {
+    match verify_decoded(token, secret, leeway, now, max_token_size) {
+      Ok(_) => report.verified += 1,
+      Err(failure) => {
+        report.failed += 1;
+        let reason = failure.to_string();
+        match report.failures.iter_mut().find(|(r, _)| *r == reason) {
+          Some((_, count)) => *count += 1,
+          None => report.failures.push((reason, 1)),
+        }
+      }
+    }
+  }
+  report.elapsed = start.elapsed();
+  report.failures.sort_by_key(|&(_, count)| Reverse(count));
+  report
+}
+
+#[cfg(test)]
+mod tests {
+  use jsonwebtoken::{encode, EncodingKey, Header};
+  use serde_json::json;
+
+  use super::*;
+  use crate::app::jwt_decoder::DEFAULT_MAX_TOKEN_SIZE;
+
+  const NOW: i64 = 1_700_000_000;
+
+  #[test]
+  fn test_bench() {
+    let token = |exp: i64| {
+      encode(
+        &Header::default(),
+        &json!({ "exp": exp }),
+        &EncodingKey::from_secret(b"secret"),
+      )
+      .unwrap()
+    };
+    let input = format!(
+      "{}\n\nAuthorization: Bearer {}\n{}\nnot-a-token\n{}\n",
+      token(NOW + 60),
+      token(NOW + 60),
+      token(NOW - 600),
+      token(NOW - 600)
+    );
+
+    let report = bench(&input, "secret", 0, NOW, 2, DEFAULT_MAX_TOKEN_SIZE);
+    assert_eq!((report.verified, report.failed), (4, 6));
+    assert_eq!(
+      report.failures,
+      [("token expired".into(), 4), ("malformed token".into(), 2)]
+    );
+    let summary = report.to_string();
+    assert!(summary.starts_with("verified 4 of 10 tokens, 6 failed, in "));
+    assert!(summary.ends_with("tokens/s)\n  4 × token expired\n  2 × malformed token"));
+
+    let report = bench("", "secret", 0, NOW, 1, DEFAULT_MAX_TOKEN_SIZE);
+    assert_eq!(report.total(), 0);
+    assert_eq!(report.tokens_per_second(), 0.0);
+
+    let report = bench(&input, "secret", 0, NOW, 1, 32);
+    assert_eq!(
+      report.failures,
+      [("token too large".into(), 4), ("malformed token".into(), 1)]
+    );
+  }
+}
@@ -0,0 +1,216 @@
+//! Subject and validity of X.509 certificates used as the key to verify tokens with
+
+use std::{fmt, str::from_utf8};
+
+use chrono::{NaiveDateTime, TimeZone, Utc};
+
+use super::utils::{pem_to_der, slurp_file, strip_leading_symbol};
+
+/// The parts of a certificate shown when verifying with it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+  /// distinguished name like `O=jwt-ui, CN=jwt-ui test`
+  pub subject: String,
+  /// unix time of `notBefore`
+  pub not_before: i64,
+  /// unix time of `notAfter`
+  pub not_after: i64,
+}
+
+impl Certificate {
+  /// the leaf certificate of a PEM bundle, i.e. the first one. `None` when the PEM holds something
+  /// else first, like a public key
+  pub fn from_pem(pem: &[u8]) -> Option<Self> {
+    let first = from_utf8(pem)
+      .ok()?
+      .lines()
+      .find(|line| line.starts_with("-----BEGIN"))?;
+    if first.trim() != "-----BEGIN CERTIFICATE-----" {
+      return None;
+    }
+    Self::from_der(&pem_to_der(pem)?)
+  }
+
+  /// reads the `tbsCertificate` of a DER encoded certificate as per RFC 5280 section 4.1
+  pub fn from_der(der: &[u8]) -> Option<Self> {
+    let (SEQUENCE, certificate, _) = der_element(der)? else {
+      return None;
+    };
+    let (SEQUENCE, tbs, _) = der_element(certificate)? else {
+      return None;
+    };
+    let mut rest = tbs;
+    // the version is optional and explicitly tagged
+    if let (VERSION, _, after) = der_element(rest)? {
+      rest = after;
+    }
+    // serial number, signature algorithm and issuer
+    for _ in 0..3 {
+      rest = der_element(rest)?.2;
+    }
+    let (SEQUENCE, validity, rest) = der_element(rest)? else {
+      return None;
+    };
+    let (SEQUENCE, subject, _) = der_element(rest)? else {
+      return None;
+    };
+    let (not_before, after) = der_time(validity)?;
+    let (not_after, _) = der_time(after)?;
+    Some(Certificate {
+      subject: distinguished_name(subject)?,
+      not_before,
+      not_after,
+    })
+  }
+
+  pub fn is_expired(&self, now: i64) -> bool {
+    now > self.not_after
+  }
+}
+
+impl fmt::Display for Certificate {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}, valid {} to {}",
+      self.subject,
+      date(self.not_before),
+      date(self.not_after)
+    )
+  }
+}
+
+/// day of a unix time, e.g. `2024-01-01`
+pub fn date(timestamp: i64) -> String {
+  Utc.timestamp_opt(timestamp, 0).single().map_or_else(
+    || timestamp.to_string(),
+    |d| d.format("%Y-%m-%d").to_string(),
+  )
+}
+
+/// the certificate an `@path` secret points at, `None` for other secrets
+pub fn certificate_of_secret(secret: &str) -> Option<Certificate> {
+  if !secret.starts_with('@') {
+    return None;
+  }
+  Certificate::from_pem(&slurp_file(strip_leading_symbol(secret)).ok()?)
+}
+
+const SEQUENCE: u8 = 0x30;
+const SET: u8 = 0x31;
+const OID: u8 = 0x06;
+const UTC_TIME: u8 = 0x17;
+const GENERALIZED_TIME: u8 = 0x18;
+/// `[0] EXPLICIT Version`
+const VERSION: u8 = 0xa0;
+
+// DER encoded object identifiers of the attributes shown in a distinguished name
+const NAME_ATTRIBUTES: [(&[u8], &str); 6] = [
+  (&[0x55, 0x04, 0x03], "CN"),
+  (&[0x55, 0x04, 0x06], "C"),
+  (&[0x55, 0x04, 0x07], "L"),
+  (&[0x55, 0x04, 0x08], "ST"),
+  (&[0x55, 0x04, 0x0a], "O"),
+  (&[0x55, 0x04, 0x0b], "OU"),
+];
+
+/// tag and content of the DER element at the start of `der`, and what follows it
+pub fn der_element(der: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+  let (&tag, rest) = der.split_first()?;
+  let (&len, mut rest) = rest.split_first()?;
+  let len = if len & 0x80 == 0 {
+    usize::from(len)
+  } else {
+    // long form, the low bits give the number of length bytes
+    let count = usize::from(len & 0x7f);
+    if count == 0 || count > 4 || rest.len() < count {
+      return None;
+    }
+    let (bytes, after) = rest.split_at(count);
+    rest = after;
+    bytes.iter().fold(0, |len, b| len << 8 | usize::from(*b))
+  };
+  (rest.len() >= len).then(|| (tag, &rest[..len], &rest[len..]))
+}
+
+/// unix time of the UTCTime or GeneralizedTime at the start of `der`, and what follows it
+fn der_time(der: &[u8]) -> Option<(i64, &[u8])> {
+  let (tag, time, rest) = der_element(der)?;
+  let time = from_utf8(time).ok()?;
+  let time = match tag {
+    // two digit years from 1950 to 2049 as per RFC 5280 section 4.1.2.5.1
+    UTC_TIME if time.get(..2)? < "50" => format!("20{time}"),
+    UTC_TIME => format!("19{time}"),
+    GENERALIZED_TIME => time.into(),
+    _ => return None,
+  };
+  let time = NaiveDateTime::parse_from_str(&time, "%Y%m%d%H%M%SZ").ok()?;
+  Some((time.and_utc().timestamp(), rest))
+}
+
+/// the known attributes of a `Name`, in order
+fn distinguished_name(mut der: &[u8]) -> Option<String> {
+  let mut parts = vec![];
+  while !der.is_empty() {
+    let (SET, set, rest) = der_element(der)? else {
+      return None;
+    };
+    der = rest;
+    let (SEQUENCE, attribute, _) = der_element(set)? else {
+      return None;
+    };
+    let (OID, oid, value) = der_element(attribute)? else {
+      return None;
+    };
+    let (_, value, _) = der_element(value)?;
+    if let Some((_, name)) = NAME_ATTRIBUTES.iter().find(|(known, _)| *known == oid) {
+      parts.push(format!("{name}={}", String::from_utf8_lossy(value)));
+    }
+  }
+  Some(parts.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_certificate_from_pem() {
+    let bundle = slurp_file("./test_data/test_rsa_cert_bundle.crt".into()).unwrap();
+    let certificate = Certificate::from_pem(&bundle).unwrap();
+
+    // the leaf comes first, not the CA after it
+    assert_eq!(
+      certificate.to_string(),
+      "O=jwt-ui, CN=jwt-ui test, valid 2024-01-01 to 2124-01-01"
+    );
+    assert!(!certificate.is_expired(1_700_000_000));
+
+    let expired = certificate_of_secret("@./test_data/test_rsa_expired_cert.pem").unwrap();
+    assert_eq!(expired.subject, "CN=jwt-ui expired");
+    assert_eq!(expired.not_after, 978_307_200);
+    assert!(expired.is_expired(1_700_000_000));
+
+    let public_key = slurp_file("./test_data/test_rsa_public_key.pem".into()).unwrap();
+    assert_eq!(Certificate::from_pem(&public_key), None);
+    assert_eq!(
+      certificate_of_secret("./test_data/test_rsa_cert_bundle.crt"),
+      None
+    );
+  }
+
+  #[test]
+  fn test_der_element() {
+    assert_eq!(
+      der_element(&[0x04, 0x02, 0xaa, 0xbb, 0xcc]),
+      Some((0x04, &[0xaa, 0xbb][..], &[0xcc][..]))
+    );
+    let long = [&[0x04, 0x81, 0x80][..], &[0; 128]].concat();
+    assert_eq!(
+      der_element(&long).map(|(_, content, _)| content.len()),
+      Some(128)
+    );
+    // the content is cut short
+    assert_eq!(der_element(&[0x04, 0x03, 0xaa]), None);
+  }
+}
@@ -0,0 +1,213 @@
+//! Typed access to the registered claims of a payload (RFC 7519 section 4.1), for library users
+//! who'd rather not read them from `serde_json::Value`
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use super::jwt_decoder::Payload;
+
+/// short description of the registered claims and the common OpenID Connect ones
+pub fn claim_description(claim: &str) -> Option<&'static str> {
+  let description = match claim {
+    "iss" => "Issuer",
+    "sub" => "Subject",
+    "aud" => "Audience",
+    "exp" => "Expiration time",
+    "nbf" => "Not before",
+    "iat" => "Issued at",
+    "jti" => "JWT ID",
+    "auth_time" => "Time the user authenticated",
+    "nonce" => "Value binding the token to the client's request",
+    "acr" => "Authentication context class, the assurance level met",
+    "amr" => "Authentication methods used",
+    "azp" => "Authorized party the token was issued to",
+    "sid" => "Session ID",
+    _ => return None,
+  };
+  Some(description)
+}
+
+/// the methods of the `amr` claim registered by RFC 8176
+pub fn amr_method_description(method: &str) -> Option<&'static str> {
+  let description = match method {
+    "face" => "Facial recognition",
+    "fpt" => "Fingerprint",
+    "geo" => "Geolocation",
+    "hwk" => "Proof of possession of a hardware key",
+    "iris" => "Iris scan",
+    "kba" => "Knowledge-based authentication",
+    "mca" => "Multiple-channel authentication",
+    "mfa" => "Multiple-factor authentication",
+    "otp" => "One-time password",
+    "pin" => "PIN",
+    "pwd" => "Password",
+    "rba" => "Risk-based authentication",
+    "retina" => "Retina scan",
+    "sc" => "Smart card",
+    "sms" => "Confirmation by SMS",
+    "swk" => "Proof of possession of a software key",
+    "tel" => "Confirmation by telephone call",
+    "user" => "User presence test",
+    "vbm" => "Voice biometric",
+    "wia" => "Windows integrated authentication",
+    _ => return None,
+  };
+  Some(description)
+}
+
+/// The `aud` claim, a single audience or an array of them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Audience {
+  Single(String),
+  Multiple(Vec<String>),
+}
+
+impl Audience {
+  /// reads `aud` leniently, elements of an array that aren't strings are left out
+  pub fn from_value(value: &Value) -> Option<Self> {
+    match value {
+      Value::String(aud) => Some(Audience::Single(aud.clone())),
+      Value::Array(aud) => Some(Audience::Multiple(
+        aud
+          .iter()
+          .filter_map(Value::as_str)
+          .map(String::from)
+          .collect(),
+      )),
+      _ => None,
+    }
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &str> {
+    let audiences = match self {
+      Audience::Single(aud) => std::slice::from_ref(aud),
+      Audience::Multiple(aud) => aud.as_slice(),
+    };
+    audiences.iter().map(String::as_str)
+  }
+
+  pub fn contains(&self, audience: &str) -> bool {
+    self.iter().any(|aud| aud == audience)
+  }
+}
+
+/// The registered claims of a payload with their types, the other claims are kept as they are in
+/// `extra`. Time claims are unix timestamps, fractional ones are truncated
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandardClaims {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub iss: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub sub: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub aud: Option<Audience>,
+  #[serde(default, deserialize_with = "numeric_date")]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub exp: Option<i64>,
+  #[serde(default, deserialize_with = "numeric_date")]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub nbf: Option<i64>,
+  #[serde(default, deserialize_with = "numeric_date")]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub iat: Option<i64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub jti: Option<String>,
+  #[serde(flatten)]
+  pub extra: HashMap<String, Value>,
+}
+
+/// a NumericDate, which may have a fraction of a second
+fn numeric_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+  let Some(date) = Option::<serde_json::Number>::deserialize(deserializer)? else {
+    return Ok(None);
+  };
+  date
+    .as_i64()
+    .or_else(|| date.as_f64().map(|date| date as i64))
+    .map(Some)
+    .ok_or_else(|| serde::de::Error::custom(format!("{date} is not a NumericDate")))
+}
+
+impl Payload {
+  /// the registered claims of the payload, an error when one of them has the wrong type. Time
+  /// claims converted to dates for display can't be read this way
+  pub fn standard_claims(&self) -> Result<StandardClaims, serde_json::Error> {
+    serde_json::from_value(Value::Object(self.0.clone()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn payload(claims: Value) -> Payload {
+    Payload(serde_json::from_value(claims).unwrap())
+  }
+
+  #[test]
+  fn test_standard_claims() {
+    let claims = payload(json!({
+      "iss": "issuer",
+      "sub": "1234567890",
+      "aud": ["api", "web"],
+      "exp": 1_700_000_000,
+      "iat": 1_600_000_000.75,
+      "jti": "id",
+      "roles": ["admin"],
+    }))
+    .standard_claims()
+    .unwrap();
+    assert_eq!(claims.iss.as_deref(), Some("issuer"));
+    assert_eq!(claims.sub.as_deref(), Some("1234567890"));
+    assert_eq!(
+      claims.aud,
+      Some(Audience::Multiple(vec!["api".into(), "web".into()]))
+    );
+    assert_eq!(claims.exp, Some(1_700_000_000));
+    assert_eq!(claims.iat, Some(1_600_000_000));
+    assert_eq!(claims.nbf, None);
+    assert_eq!(claims.jti.as_deref(), Some("id"));
+    assert_eq!(
+      claims.extra,
+      HashMap::from([("roles".into(), json!(["admin"]))])
+    );
+
+    let claims = payload(json!({ "aud": "api" })).standard_claims().unwrap();
+    assert_eq!(claims.aud, Some(Audience::Single("api".into())));
+    assert_eq!(
+      serde_json::to_value(&claims).unwrap(),
+      json!({ "aud": "api" })
+    );
+
+    assert!(payload(json!({ "exp": "tomorrow" }))
+      .standard_claims()
+      .is_err());
+    assert!(payload(json!({ "sub": 1 })).standard_claims().is_err());
+  }
+
+  #[test]
+  fn test_claim_descriptions() {
+    assert_eq!(
+      claim_description("acr").map(|d| d.starts_with("Authentication context")),
+      Some(true)
+    );
+    assert_eq!(claim_description("roles"), None);
+    assert_eq!(amr_method_description("otp"), Some("One-time password"));
+    assert_eq!(amr_method_description("custom"), None);
+  }
+
+  #[test]
+  fn test_audience() {
+    let aud = Audience::from_value(&json!(["api", 1, "web"])).unwrap();
+    assert_eq!(aud.iter().collect::<Vec<_>>(), ["api", "web"]);
+    assert!(aud.contains("web"));
+    assert!(!aud.contains("other"));
+    assert!(Audience::from_value(&json!("api")).unwrap().contains("api"));
+    assert_eq!(Audience::from_value(&json!(42)), None);
+  }
+}
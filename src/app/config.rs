@@ -0,0 +1,266 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  env,
+  path::PathBuf,
+  str::FromStr,
+};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use super::jwt_decoder::DEFAULT_MAX_TOKEN_SIZE;
+
+/// a year, tokens are rarely meant to live longer
+pub const DEFAULT_MAX_EXP_IN_FUTURE: i64 = 365 * 86400;
+
+/// Settings that tweak validation and display behaviour
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+  /// Minimum expected token lifetime (`exp - iat`) in seconds
+  pub min_lifetime: i64,
+  /// Maximum expected token lifetime (`exp - iat`) in seconds
+  pub max_lifetime: i64,
+  /// Seconds from now that `exp` is expected within, later ones are as good as never expiring
+  pub max_exp_in_future: i64,
+  /// Allowed clock skew in seconds when checking time claims
+  pub leeway: i64,
+  /// Reject token segments that aren't unpadded base64url
+  pub strict: bool,
+  /// Enable the vim keys that aren't on by default, `gg`/`G` to scroll to the top/bottom
+  pub vim_keys: bool,
+  /// Lines or rows scrolled by the arrow keys and the mouse wheel
+  pub scroll_step: usize,
+  /// Lines or rows scrolled by page up/down, at least 1
+  pub page_step: usize,
+  /// Recommended minimum HMAC secret length in bytes per `alg`, shorter secrets are warned about
+  pub min_secret_lengths: BTreeMap<String, usize>,
+  /// `alg` values warned about as weak or deprecated in the header, with the reason why
+  pub weak_algs: BTreeMap<String, String>,
+  /// URL of a web decoder to open tokens in, `{token}` is replaced by the token. Opening tokens is
+  /// disabled when not set
+  pub web_decoder_url: Option<String>,
+  /// URL the `curl` command of the copy menu calls with the token. Copying it is disabled when not
+  /// set
+  pub curl_url: Option<String>,
+  /// Treat tokens whose signature wasn't verified as invalid, also in the exit code of `--stdout`
+  pub require_signature: bool,
+  /// Unix time time claims are checked against instead of the system clock, for reproducible
+  /// validation
+  pub now: Option<i64>,
+  /// Audiences expected in the `aud` claim, matched per `audience_mode`. Not checked when empty
+  pub audiences: Vec<String>,
+  pub audience_mode: AudienceMode,
+  /// Whether a missing audience makes the token invalid or is only a warning
+  pub audience_severity: Severity,
+  /// Issuer expected in the `iss` claim. Not checked when not set
+  pub issuer: Option<String>,
+  /// Whether another issuer makes the token invalid or is only a warning
+  pub issuer_severity: Severity,
+  /// `jti` values of tokens seen before, a token with one of them is a replay. Not checked when
+  /// not set
+  pub jti_denylist: Option<BTreeSet<String>>,
+  /// Verify with the key of an expired certificate instead of failing
+  pub allow_expired_cert: bool,
+  /// Don't warn about tokens without `exp`, for systems that issue tokens that never expire
+  pub allow_no_exp: bool,
+  /// Claims shown as UTC dates like `exp`, `iat` and `nbf`, e.g. `auth_time`. Display only, they
+  /// aren't validated
+  pub time_claims: Vec<String>,
+  /// How time claims shown as UTC dates are formatted
+  pub date_format: DateFormat,
+  /// Friendly labels shown for cryptic claim names like `x-tn`, display only. The JSON and copies
+  /// keep the actual claim names
+  pub claim_labels: BTreeMap<String, String>,
+  /// Claim shown in the terminal window title, e.g. `sub` or `email`. The title is left alone when
+  /// not set
+  pub title_claim: Option<String>,
+  /// Tokens longer than this many bytes are rejected instead of decoded
+  pub max_token_size: usize,
+  /// Ask before quitting with `q` while a secret is entered, so that a stray key doesn't lose it
+  pub confirm_quit: bool,
+  /// Keep the token field edited and count each token pasted in turn, for triaging many tokens
+  pub batch: bool,
+  /// `alg` every token is verified with whatever its header says, pinned for the session. Tokens
+  /// with another `alg` are flagged
+  pub pinned_alg: Option<String>,
+  /// Labelled keys tried in order when no secret is entered, like `prod` and `staging`
+  pub keys: Vec<(String, String)>,
+}
+
+/// How the expected audiences are matched against the `aud` claim
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AudienceMode {
+  /// at least one of them is in `aud`
+  #[default]
+  Any,
+  /// all of them are in `aud`
+  All,
+}
+
+impl FromStr for AudienceMode {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "any" => Ok(AudienceMode::Any),
+      "all" => Ok(AudienceMode::All),
+      _ => Err(format!("`{s}` is neither `any` nor `all`")),
+    }
+  }
+}
+
+/// How a failed claim check counts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Severity {
+  /// the token is invalid, and printing to STDOUT exits with 1
+  #[default]
+  Error,
+  /// the token is flagged in the warnings but stays valid
+  Warning,
+}
+
+impl FromStr for Severity {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "error" => Ok(Severity::Error),
+      "warning" => Ok(Severity::Warning),
+      _ => Err(format!("`{s}` is neither `error` nor `warning`")),
+    }
+  }
+}
+
+/// Format of the time claims shown as UTC dates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DateFormat {
+  /// `2023-11-14 22:13:20 UTC`
+  #[default]
+  Human,
+  /// ISO 8601 with the offset, `2023-11-14T22:13:20+00:00`
+  Iso8601,
+  /// `Tue, 14 Nov 2023 22:13:20 +0000`
+  Rfc2822,
+}
+
+/// the human format, which chrono has no preset for
+const HUMAN_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S UTC";
+
+impl DateFormat {
+  pub const ALL: [DateFormat; 3] = [DateFormat::Human, DateFormat::Iso8601, DateFormat::Rfc2822];
+
+  /// name taken by `--date-format` and the settings
+  pub fn name(&self) -> &'static str {
+    match self {
+      DateFormat::Iso8601 => "iso8601",
+      DateFormat::Human => "human",
+      DateFormat::Rfc2822 => "rfc2822",
+    }
+  }
+
+  pub fn format(&self, date: DateTime<Utc>) -> String {
+    match self {
+      DateFormat::Iso8601 => date.to_rfc3339(),
+      DateFormat::Human => date.format(HUMAN_DATE_FORMAT).to_string(),
+      DateFormat::Rfc2822 => date.to_rfc2822(),
+    }
+  }
+
+  /// unix timestamp of a date in any of the formats, so that converted claims can still be checked
+  pub fn parse_timestamp(date: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(date)
+      .or_else(|_| DateTime::parse_from_rfc2822(date))
+      .map(|date| date.timestamp())
+      .ok()
+      .or_else(|| {
+        NaiveDateTime::parse_from_str(date, HUMAN_DATE_FORMAT)
+          .ok()
+          .map(|date| date.and_utc().timestamp())
+      })
+  }
+}
+
+impl FromStr for DateFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    DateFormat::ALL
+      .into_iter()
+      .find(|format| format.name() == s)
+      .ok_or_else(|| format!("`{s}` is neither `iso8601`, `human` nor `rfc2822`"))
+  }
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Config {
+      min_lifetime: 60,
+      max_lifetime: 86400,
+      max_exp_in_future: DEFAULT_MAX_EXP_IN_FUTURE,
+      leeway: 60,
+      strict: false,
+      vim_keys: false,
+      scroll_step: 1,
+      page_step: 10,
+      // the size of the hash output, as RFC 7518 requires
+      min_secret_lengths: [("HS256", 32), ("HS384", 48), ("HS512", 64)]
+        .into_iter()
+        .map(|(alg, len)| (alg.into(), len))
+        .collect(),
+      weak_algs: [
+        ("none", "the token is not signed"),
+        ("RS1", "RSA with SHA-1 is deprecated, SHA-1 is broken"),
+        ("HS1", "HMAC with SHA-1 is deprecated"),
+      ]
+      .into_iter()
+      .map(|(alg, reason)| (alg.into(), reason.into()))
+      .collect(),
+      web_decoder_url: None,
+      curl_url: None,
+      require_signature: false,
+      now: None,
+      audiences: vec![],
+      audience_mode: AudienceMode::Any,
+      audience_severity: Severity::Error,
+      issuer: None,
+      issuer_severity: Severity::Error,
+      jti_denylist: None,
+      allow_expired_cert: false,
+      allow_no_exp: false,
+      time_claims: vec![],
+      date_format: DateFormat::Iso8601,
+      claim_labels: BTreeMap::new(),
+      title_claim: None,
+      max_token_size: DEFAULT_MAX_TOKEN_SIZE,
+      confirm_quit: false,
+      batch: false,
+      pinned_alg: None,
+      keys: vec![],
+    }
+  }
+}
+
+impl Config {
+  pub fn min_secret_length(&self, alg: &str) -> Option<usize> {
+    self.min_secret_lengths.get(alg).copied()
+  }
+
+  /// the pinned time if any, the system clock otherwise
+  pub fn now(&self) -> i64 {
+    self.now.unwrap_or_else(|| Utc::now().timestamp())
+  }
+}
+
+/// directory holding jwt-ui's config and state files. Can be overridden with `JWT_UI_CONFIG_DIR`
+pub fn config_dir() -> Option<PathBuf> {
+  if let Some(dir) = env::var_os("JWT_UI_CONFIG_DIR") {
+    return Some(PathBuf::from(dir));
+  }
+  let base = if cfg!(target_os = "windows") {
+    env::var_os("APPDATA").map(PathBuf::from)
+  } else {
+    env::var_os("XDG_CONFIG_HOME")
+      .map(PathBuf::from)
+      .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+  };
+  base.map(|dir| dir.join("jwt-ui"))
+}
@@ -0,0 +1,106 @@
+//! CSV export of selected claims for a batch of tokens
+
+use serde_json::Value;
+
+use super::jwt_decoder::{decode, extract_token, DecodeOptions};
+
+/// one CSV row per non-blank line of `input`, with a column per claim in `columns` followed by an
+/// `error` column. Claims missing from a token are empty cells, tokens that can't be decoded get
+/// an error row instead of aborting the batch. Signatures aren't verified
+pub fn claims_to_csv(
+  input: &str,
+  columns: &[String],
+  readable_dates: bool,
+  max_token_size: usize,
+) -> String {
+  let options = DecodeOptions {
+    utc_dates: readable_dates,
+    ignore_exp: true,
+    max_token_size: Some(max_token_size),
+    ..DecodeOptions::default()
+  };
+  let header = columns
+    .iter()
+    .map(String::as_str)
+    .chain(["error"])
+    .map(csv_field)
+    .collect::<Vec<_>>()
+    .join(",");
+
+  let mut rows = vec![header];
+  for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+    let token = extract_token(line).map_or(line.into(), |e| e.token);
+    let mut cells = match decode(&token, &options) {
+      Ok(decoded) => {
+        let mut cells = columns
+          .iter()
+          .map(|column| claim_cell(decoded.payload.0.get(column)))
+          .collect::<Vec<_>>();
+        cells.push(String::new());
+        cells
+      }
+      Err(e) => {
+        let mut cells = vec![String::new(); columns.len()];
+        cells.push(e.to_string());
+        cells
+      }
+    };
+    cells.iter_mut().for_each(|cell| *cell = csv_field(cell));
+    rows.push(cells.join(","));
+  }
+  rows.join("\n") + "\n"
+}
+
+fn claim_cell(value: Option<&Value>) -> String {
+  match value {
+    None | Some(Value::Null) => String::new(),
+    Some(Value::String(s)) => s.clone(),
+    Some(value) => value.to_string(),
+  }
+}
+
+/// quotes fields containing separators, quotes or line breaks as per RFC 4180
+fn csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::app::jwt_decoder::DEFAULT_MAX_TOKEN_SIZE;
+
+  #[test]
+  fn test_claims_to_csv() {
+    // {"sub":"1","email":"a@b.c","exp":1700000000,"roles":["x","y"]}
+    let token = "eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIiwiZW1haWwiOiJhQGIuYyIsImV4cCI6MTcwMDAwMDAwMCwicm9sZXMiOlsieCIsInkiXX0.c2ln";
+    let input = format!("{token}\n\nnot-a-token\nAuthorization: Bearer {token}\n");
+    let columns = ["sub", "exp", "roles", "name"].map(String::from);
+
+    assert_eq!(
+      claims_to_csv(&input, &columns, false, DEFAULT_MAX_TOKEN_SIZE),
+      format!(
+        "sub,exp,roles,name,error\n1,1700000000,\"[\"\"x\"\",\"\"y\"\"]\",,\n,,,,{}\n1,1700000000,\"[\"\"x\"\",\"\"y\"\"]\",,\n",
+        decode("not-a-token", &DecodeOptions::default()).unwrap_err()
+      )
+    );
+    assert_eq!(
+      claims_to_csv(token, &columns[..2], true, DEFAULT_MAX_TOKEN_SIZE),
+      "sub,exp,error\n1,2023-11-14 22:13:20 UTC,\n"
+    );
+    assert_eq!(
+      claims_to_csv(token, &columns[..1], false, 32),
+      "sub,error\n,token exceeds max size (32 bytes); adjust with --max-token-size\n"
+    );
+  }
+
+  #[test]
+  fn test_csv_field() {
+    assert_eq!(csv_field("plain"), "plain");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+  }
+}
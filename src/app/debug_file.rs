@@ -0,0 +1,100 @@
+//! `.jwt` debug files holding a token and its secret, one `key: value` per line
+
+use std::{fs, path::Path};
+
+use super::utils::strip_leading_symbol;
+
+/// Token and secret loaded from a debug file. Warnings never contain values from the file
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DebugFile {
+  pub token: Option<String>,
+  pub secret: String,
+  pub warnings: Vec<String>,
+}
+
+impl DebugFile {
+  /// parses `token:` and `secret:` lines, blank lines and lines starting with `#` are skipped.
+  /// Unknown keys and malformed lines produce warnings instead of failing
+  pub fn parse(content: &str) -> Self {
+    let mut file = DebugFile::default();
+    for (index, line) in content.lines().enumerate() {
+      let line_no = index + 1;
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let Some((key, value)) = line.split_once(':') else {
+        file
+          .warnings
+          .push(format!("line {line_no}: expected `key: value`, ignored"));
+        continue;
+      };
+      let value = value.trim().to_string();
+      match key.trim() {
+        "token" => file.token = Some(value),
+        "secret" => file.secret = value,
+        key => file
+          .warnings
+          .push(format!("line {line_no}: unknown key `{key}` ignored")),
+      }
+    }
+    if file.token.is_none() {
+      file.warnings.push("no `token` found".into());
+    }
+    file
+  }
+
+  /// reads and parses the file at `path`. Secret files (`@path`) are resolved relative to it
+  pub fn load(path: &Path) -> Result<Self, String> {
+    let content =
+      fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {e}", path.display()))?;
+    let mut file = Self::parse(&content);
+    if let (true, Some(dir)) = (file.secret.starts_with('@'), path.parent()) {
+      let secret_path = strip_leading_symbol(&file.secret);
+      if Path::new(&secret_path).is_relative() {
+        file.secret = format!("@{}", dir.join(secret_path).display());
+      }
+    }
+    Ok(file)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_debug_file() {
+    let file = DebugFile::parse(
+      "# reproduces the expiry bug\ntoken: eyJhbGciOiJIUzI1NiJ9.e30.sig\n\nsecret: b64:c2VjcmV0\nissuer: https://example.com\nnot a pair\n",
+    );
+
+    assert_eq!(file.token.as_deref(), Some("eyJhbGciOiJIUzI1NiJ9.e30.sig"));
+    assert_eq!(file.secret, "b64:c2VjcmV0");
+    assert_eq!(
+      file.warnings,
+      vec![
+        "line 5: unknown key `issuer` ignored".to_string(),
+        "line 6: expected `key: value`, ignored".to_string(),
+      ]
+    );
+
+    let file = DebugFile::parse("secret: a:b\n");
+    assert_eq!(file.token, None);
+    assert_eq!(file.secret, "a:b");
+    assert_eq!(file.warnings, vec!["no `token` found".to_string()]);
+  }
+
+  #[test]
+  fn test_load_debug_file() {
+    let file = DebugFile::load(Path::new("./test_data/test_debug_file.jwt")).unwrap();
+
+    assert!(file.token.is_some());
+    assert_eq!(file.secret, "@./test_data/test_hmac_secrets.txt");
+    assert!(file.warnings.is_empty());
+
+    assert!(DebugFile::load(Path::new("./test_data/nonexistent.jwt"))
+      .unwrap_err()
+      .starts_with("Unable to read ./test_data/nonexistent.jwt: "));
+  }
+}
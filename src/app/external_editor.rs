@@ -0,0 +1,185 @@
+//! Editing a field in `$VISUAL` or `$EDITOR`, for tokens and keys too long to type in the UI
+
+use std::{
+  env,
+  fs::{self, File, OpenOptions},
+  io::{self, Write},
+  path::{Path, PathBuf},
+  process::Command,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::ActiveBlock;
+
+/// fields that can be edited in the editor
+pub const EDITABLE_BLOCKS: [ActiveBlock; 5] = [
+  ActiveBlock::DecoderToken,
+  ActiveBlock::DecoderSecret,
+  ActiveBlock::EncoderHeader,
+  ActiveBlock::EncoderPayload,
+  ActiveBlock::EncoderSecret,
+];
+
+/// A field to edit in the editor once the UI is suspended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalEdit {
+  pub block: ActiveBlock,
+  pub content: String,
+}
+
+impl ExternalEdit {
+  /// secrets are written where only the user can read them and overwritten before removal
+  pub fn is_secret(&self) -> bool {
+    matches!(
+      self.block,
+      ActiveBlock::DecoderSecret | ActiveBlock::EncoderSecret
+    )
+  }
+
+  /// the token and secrets are single lines, the encoder's JSON keeps its lines
+  pub fn is_multiline(&self) -> bool {
+    matches!(
+      self.block,
+      ActiveBlock::EncoderHeader | ActiveBlock::EncoderPayload
+    )
+  }
+
+  /// writes the content to a temp file, runs `editor` on it and reads the edited content back.
+  /// The temp file is removed in any case. An empty result is an error, so that the field keeps
+  /// its value
+  pub fn run(&self, editor: &str) -> Result<String, String> {
+    let path = temp_file_path(self.is_secret());
+    let edited = write_private(&path, &self.content)
+      .map_err(|e| format!("Unable to create a temp file for the editor: {e}"))
+      .and_then(|_| run_editor(editor, &path))
+      .and_then(|_| {
+        fs::read_to_string(&path).map_err(|e| format!("Unable to read the edited value: {e}"))
+      });
+    if self.is_secret() {
+      let _ = shred(&path);
+    }
+    let _ = fs::remove_file(&path);
+
+    let edited = edited?;
+    let edited = if self.is_multiline() {
+      edited.trim_end_matches(['\n', '\r']).to_string()
+    } else {
+      // editors add a final newline
+      edited.trim().to_string()
+    };
+    if edited.is_empty() {
+      return Err("The edited value is empty".into());
+    }
+    Ok(edited)
+  }
+}
+
+/// `$VISUAL`, then `$EDITOR`, `vi` when neither is set
+pub fn editor_command() -> String {
+  ["VISUAL", "EDITOR"]
+    .into_iter()
+    .filter_map(|name| env::var(name).ok())
+    .find(|editor| !editor.trim().is_empty())
+    .unwrap_or_else(|| "vi".into())
+}
+
+/// runs the editor on `path`, it may come with arguments like `code --wait`
+fn run_editor(editor: &str, path: &Path) -> Result<(), String> {
+  let mut words = editor.split_whitespace();
+  let program = words.next().ok_or("No editor set in $VISUAL or $EDITOR")?;
+  let status = Command::new(program)
+    .args(words)
+    .arg(path)
+    .status()
+    .map_err(|e| format!("Unable to run the editor `{program}`: {e}"))?;
+  if !status.success() {
+    return Err(format!("The editor `{program}` failed with {status}"));
+  }
+  Ok(())
+}
+
+/// a new file in the temp dir, in `$XDG_RUNTIME_DIR` for secrets when there is one as it's private
+/// to the user and usually kept in memory
+fn temp_file_path(secret: bool) -> PathBuf {
+  let dir = env::var_os("XDG_RUNTIME_DIR")
+    .filter(|_| secret)
+    .map(PathBuf::from)
+    .filter(|dir| dir.is_dir())
+    .unwrap_or_else(env::temp_dir);
+  let nanos = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |d| d.subsec_nanos());
+  dir.join(format!("jwt-ui-{}-{nanos}.txt", std::process::id()))
+}
+
+/// creates the file readable by the user only, failing if it exists already
+fn write_private(path: &Path, content: &str) -> io::Result<()> {
+  let mut options = OpenOptions::new();
+  options.write(true).create_new(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600);
+  }
+  let mut file = options.open(path)?;
+  file.write_all(content.as_bytes())?;
+  file.sync_all()
+}
+
+/// overwrites the file with zeros, so that the secret doesn't linger on the disk once it's removed
+fn shred(path: &Path) -> io::Result<()> {
+  let len = fs::metadata(path)?.len();
+  let mut file = OpenOptions::new().write(true).open(path)?;
+  file.write_all(&vec![0; len as usize])?;
+  file.sync_all()?;
+  drop(file);
+  File::create(path).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn edit(block: ActiveBlock, content: &str) -> ExternalEdit {
+    ExternalEdit {
+      block,
+      content: content.into(),
+    }
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn test_external_edit_run() {
+    // `true` leaves the file as it is
+    let token = edit(ActiveBlock::DecoderToken, "a.b.c\n");
+    assert_eq!(token.run("true"), Ok("a.b.c".into()));
+    let payload = edit(ActiveBlock::EncoderPayload, "{\n  \"sub\": \"1\"\n}\n");
+    assert_eq!(payload.run("true"), Ok("{\n  \"sub\": \"1\"\n}".into()));
+
+    let secret = edit(ActiveBlock::DecoderSecret, "secret");
+    assert!(secret.is_secret());
+    assert_eq!(secret.run("true"), Ok("secret".into()));
+
+    assert_eq!(
+      edit(ActiveBlock::DecoderToken, " \n").run("true"),
+      Err("The edited value is empty".into())
+    );
+    assert!(edit(ActiveBlock::DecoderToken, "a.b.c")
+      .run("false")
+      .unwrap_err()
+      .starts_with("The editor `false` failed with"));
+    assert!(edit(ActiveBlock::DecoderToken, "a.b.c")
+      .run("jwt-ui-no-such-editor")
+      .unwrap_err()
+      .starts_with("Unable to run the editor `jwt-ui-no-such-editor`"));
+  }
+
+  #[test]
+  fn test_shred() {
+    let path = temp_file_path(true);
+    write_private(&path, "secret").unwrap();
+    shred(&path).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), Vec::<u8>::new());
+    fs::remove_file(&path).unwrap();
+  }
+}
@@ -0,0 +1,254 @@
+//! Guided tour of the problems found with the decoded token: each key press focuses the block of
+//! the next one and highlights the claim or header parameter it's about
+
+use super::{
+  advisories::Advisory, jwt_decoder::Decoder, summary::Clause, utils::Segment, ActiveBlock, App,
+  Route, RouteId,
+};
+
+/// A problem with the token, from the decode error, the failed checks of the summary and the
+/// warnings of the blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+  pub message: String,
+  /// segment the problem is in, `None` for the token as a whole
+  pub segment: Option<Segment>,
+  /// top level claim or header parameter the problem is about, like `exp` or `alg`
+  pub claim: Option<&'static str>,
+}
+
+impl Issue {
+  fn new(
+    message: impl Into<String>,
+    segment: Option<Segment>,
+    claim: Option<&'static str>,
+  ) -> Self {
+    Issue {
+      message: message.into(),
+      segment,
+      claim,
+    }
+  }
+
+  fn block(&self) -> ActiveBlock {
+    match self.segment {
+      None => ActiveBlock::DecoderToken,
+      Some(Segment::Header) => ActiveBlock::DecoderHeader,
+      Some(Segment::Payload) => ActiveBlock::DecoderPayload,
+      Some(Segment::Signature) => ActiveBlock::DecoderSecret,
+    }
+  }
+}
+
+/// where a failed clause of the summary is, like the `exp` claim for `expired 2m ago`
+fn clause_location(clause: &str) -> (Segment, Option<&'static str>) {
+  match clause {
+    c if c.starts_with("signature") => (Segment::Signature, None),
+    c if c.starts_with("expired") || c == "missing exp" => (Segment::Payload, Some("exp")),
+    c if c.starts_with("not valid for") => (Segment::Payload, Some("nbf")),
+    c if c.starts_with("aud") => (Segment::Payload, Some("aud")),
+    c if c.starts_with("iss") => (Segment::Payload, Some("iss")),
+    c if c.starts_with("jti") => (Segment::Payload, Some("jti")),
+    _ => (Segment::Payload, None),
+  }
+}
+
+/// the claim or header parameter an advisory is about
+fn advisory_claim(advisory: &Advisory) -> Option<&'static str> {
+  match advisory.code {
+    "no_exp" | "exp_far_future" | "lifetime_too_short" | "lifetime_too_long" => Some("exp"),
+    "iat_in_future" => Some("iat"),
+    "aud_mismatch" => Some("aud"),
+    "iss_mismatch" => Some("iss"),
+    "no_jti" => Some("jti"),
+    "weak_alg" | "alg_not_pinned" => Some("alg"),
+    "b64_not_critical" => Some("b64"),
+    code if code.starts_with("crit_") => Some("crit"),
+    "jku_url" => Some("jku"),
+    "x5u_url" => Some("x5u"),
+    _ => None,
+  }
+}
+
+/// the issues in the order they're toured: the decode error, the failed checks in the order of
+/// the summary, then the warnings from the header to the signature
+pub fn issues(decoder: &Decoder) -> Vec<Issue> {
+  let mut issues = vec![];
+  if let Some(error) = decoder.decode_error() {
+    issues.push(Issue::new(error.to_string(), error.segment(), None));
+  }
+  if let Some(summary) = &decoder.summary {
+    for clause in &summary.clauses {
+      if let Clause::Fail(clause) = clause {
+        let (segment, claim) = clause_location(clause);
+        issues.push(Issue::new(clause.as_str(), Some(segment), claim));
+      }
+    }
+  }
+  let advisories = [
+    (&decoder.header_advisories, Segment::Header),
+    (&decoder.advisories, Segment::Payload),
+    (&decoder.payload_advisories, Segment::Payload),
+    (&decoder.secret_advisories, Segment::Signature),
+  ];
+  for (advisories, segment) in advisories {
+    for advisory in advisories {
+      issues.push(Issue::new(
+        advisory.message.as_str(),
+        Some(segment),
+        advisory_claim(advisory),
+      ));
+    }
+  }
+  issues
+}
+
+/// line of the top level `claim` in pretty-printed JSON
+pub fn claim_line(json: &str, claim: &str) -> Option<usize> {
+  let prefix = format!("  \"{claim}\":");
+  json.lines().position(|line| line.starts_with(&prefix))
+}
+
+/// focuses the block of the issue after the current one, wrapping around, and scrolls its claim
+/// into view. Tells `Issue 2/4: …` or that there's nothing to tour
+pub fn next_issue(app: &mut App) {
+  let decoder = &mut app.data.decoder;
+  let issues = issues(decoder);
+  if issues.is_empty() {
+    decoder.current_issue = None;
+    app.notify("No issues found with the token");
+    return;
+  }
+  let index = match &decoder.current_issue {
+    Some((index, _)) => (index + 1) % issues.len(),
+    None => 0,
+  };
+  let issue = issues[index].clone();
+  if let Some(claim) = issue.claim {
+    match issue.segment {
+      Some(Segment::Header) => {
+        if let Some(line) = claim_line(&decoder.header.get_txt(), claim) {
+          decoder.header.offset = decoder.header.row_of_line(line);
+        }
+      }
+      Some(Segment::Payload) if decoder.payload_as_table => {
+        let row = decoder
+          .payload_table
+          .items
+          .iter()
+          .position(|(k, _)| k == claim);
+        if row.is_some() {
+          decoder.payload_table.state.select(row);
+        }
+      }
+      Some(Segment::Payload) => {
+        if let Some(line) = claim_line(&decoder.payload.get_txt(), claim) {
+          decoder.payload.offset = decoder.payload.row_of_line(line);
+        }
+      }
+      _ => {}
+    }
+  }
+  decoder.blocks.set_item(Route {
+    id: RouteId::Decoder,
+    active_block: issue.block(),
+  });
+  app.push_navigation_route(*app.data.decoder.blocks.get_active_item());
+  app.notify(&format!(
+    "Issue {}/{}: {}",
+    index + 1,
+    issues.len(),
+    issue.message
+  ));
+  app.data.decoder.current_issue = Some((index, issue));
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_next_issue() {
+    // {"alg":"none"}.{"sub":"1","exp":1,"iat":4102444800}
+    let token = "eyJhbGciOiJub25lIn0.eyJzdWIiOiIxIiwiZXhwIjoxLCJpYXQiOjQxMDI0NDQ4MDB9.";
+    let mut app = App::new(250, Some(token.into()), "".into());
+    app.config.now = Some(1_700_000_000);
+    app.on_tick();
+
+    let tour = issues(&app.data.decoder);
+    let locations = tour
+      .iter()
+      .map(|issue| (issue.segment, issue.claim))
+      .collect::<Vec<_>>();
+    assert!(locations.contains(&(Some(Segment::Payload), Some("exp"))));
+    assert!(locations.contains(&(Some(Segment::Header), Some("alg"))));
+    assert!(locations.contains(&(Some(Segment::Payload), Some("iat"))));
+
+    next_issue(&mut app);
+    assert_eq!(app.data.decoder.current_issue, Some((0, tour[0].clone())));
+    assert_eq!(
+      app.notification.as_ref().unwrap().message,
+      format!("Issue 1/{}: {}", tour.len(), tour[0].message)
+    );
+    assert_eq!(app.get_current_route().active_block, tour[0].block());
+
+    // wraps around after the last one
+    for _ in 1..tour.len() {
+      next_issue(&mut app);
+    }
+    assert_eq!(
+      app.data.decoder.current_issue.as_ref().map(|(i, _)| *i),
+      Some(tour.len() - 1)
+    );
+    next_issue(&mut app);
+    assert_eq!(
+      app.data.decoder.current_issue.as_ref().map(|(i, _)| *i),
+      Some(0)
+    );
+  }
+
+  #[test]
+  fn test_next_issue_scrolls_to_claim() {
+    // {"alg":"HS256"}.{"c0":0,…,"c29":29,"iat":4102444800}
+    let claims = (0..30)
+      .map(|i| format!("\"c{i}\":{i}"))
+      .chain(["\"iat\":4102444800".to_string()])
+      .collect::<Vec<_>>()
+      .join(",");
+    let token = format!(
+      "eyJhbGciOiJIUzI1NiJ9.{}.c2ln",
+      base64::Engine::encode(
+        &base64::engine::general_purpose::URL_SAFE_NO_PAD,
+        format!("{{{claims}}}")
+      )
+    );
+    let mut app = App::new(250, Some(token), "".into());
+    app.config.now = Some(1_700_000_000);
+    app.on_tick();
+
+    let tour = issues(&app.data.decoder);
+    let issued = tour
+      .iter()
+      .position(|issue| issue.claim == Some("iat"))
+      .unwrap();
+    for _ in 0..=issued {
+      next_issue(&mut app);
+    }
+    assert_eq!(
+      app.get_current_route().active_block,
+      ActiveBlock::DecoderPayload
+    );
+    assert_eq!(app.data.decoder.payload.offset, 31);
+  }
+
+  #[test]
+  fn test_no_issues() {
+    let mut app = App::new(250, None, "".into());
+    next_issue(&mut app);
+    assert_eq!(app.data.decoder.current_issue, None);
+    assert_eq!(
+      app.notification.as_ref().unwrap().message,
+      "No issues found with the token"
+    );
+  }
+}
@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
+
 use jsonwebtoken::{errors::Error, Algorithm, EncodingKey, Header};
+use serde_json::Value;
 
 use super::{
-  jwt_decoder::Payload,
   models::{BlockState, ScrollableTxt},
   utils::{get_secret_from_file_or_input, JWTError, JWTResult, SecretType},
   ActiveBlock, App, Route, RouteId, TextAreaInput, TextInput,
@@ -94,7 +96,9 @@ fn encode_token(args: &EncodeArgs) -> JWTResult<String> {
     Ok(header) => {
       let alg = header.alg;
 
-      let payload: Result<Payload, serde_json::Error> = serde_json::from_str(&args.payload);
+      // claims are signed sorted by key, whatever order they are written in
+      let payload: Result<BTreeMap<String, Value>, serde_json::Error> =
+        serde_json::from_str(&args.payload);
       match payload {
         Ok(payload) => {
           let encoding_key = encoding_key_from_secret(&alg, &args.secret)?;
@@ -107,6 +111,25 @@ fn encode_token(args: &EncodeArgs) -> JWTResult<String> {
   }
 }
 
+/// signs the `claims` JSON object with `alg` and the key of `secret`, in the formats of the
+/// decoder's secret, for `gen`. The header is `{"typ":"JWT","alg":…}` with the `kid` when given
+pub fn generate_token(
+  claims: &str,
+  alg: Algorithm,
+  secret: &str,
+  kid: Option<&str>,
+) -> JWTResult<String> {
+  let header = Header {
+    kid: kid.map(String::from),
+    ..Header::new(alg)
+  };
+  encode_token(&EncodeArgs {
+    header: serde_json::to_string(&header)?,
+    payload: claims.trim().into(),
+    secret: secret.into(),
+  })
+}
+
 pub fn encoding_key_from_secret(alg: &Algorithm, secret_string: &str) -> JWTResult<EncodingKey> {
   let (secret, file_type) = get_secret_from_file_or_input(alg, secret_string);
   let secret = secret?;
@@ -155,7 +178,51 @@ mod tests {
   use tui_textarea::TextArea;
 
   use super::*;
-  use crate::app::jwt_decoder::{decode_token, DecodeArgs};
+  use crate::app::jwt_decoder::{decode, decode_token, DecodeArgs, DecodeOptions};
+
+  #[test]
+  fn test_generate_token() {
+    let claims = r#"{"sub": "1", "admin": true}"#;
+    for (alg, signing_key, verifying_key) in [
+      (Algorithm::HS256, "hex:736563726574", "secret"),
+      (Algorithm::HS512, "b64:c2VjcmV0", "secret"),
+      (
+        Algorithm::RS256,
+        "@./test_data/test_rsa_private_key.pem",
+        "@./test_data/test_rsa_public_key.pem",
+      ),
+      (
+        Algorithm::ES256,
+        "@./test_data/test_ecdsa_private_key.pk8",
+        "@./test_data/test_ecdsa_public_key.pk8",
+      ),
+      (
+        Algorithm::EdDSA,
+        "@./test_data/test_eddsa_private_key.pem",
+        "@./test_data/test_eddsa_public_key.pem",
+      ),
+    ] {
+      let token = generate_token(claims, alg, signing_key, Some("k1")).unwrap();
+      let options = DecodeOptions {
+        secret: verifying_key.into(),
+        ignore_exp: true,
+        ..DecodeOptions::default()
+      };
+      let decoded = decode(&token, &options).unwrap();
+      assert!(decoded.is_verified(), "{alg:?}");
+      assert_eq!(decoded.header.alg, format!("{alg:?}"));
+      assert_eq!(decoded.header.kid.as_deref(), Some("k1"));
+      assert_eq!(decoded.payload.0["admin"], Value::Bool(true));
+    }
+
+    assert_eq!(
+      generate_token("[1]", Algorithm::HS256, "secret", None)
+        .unwrap_err()
+        .to_string(),
+      "Error parsing payload: invalid type: sequence, expected a map at line 1 column 0"
+    );
+    assert!(generate_token(claims, Algorithm::RS256, "secret", None).is_err());
+  }
 
   #[test]
   fn test_encode_hmac_jwt_token_with_valid_payload_and_defaults() {
@@ -1,9 +1,13 @@
-use std::fmt;
+use std::{collections::BTreeMap, fmt, fs, path::Path, sync::OnceLock};
 
 use crossterm::event::KeyCode;
+use serde_derive::Deserialize;
 
 use crate::event::Key;
 
+/// file in the config dir the keybindings are remapped in
+pub const KEYBINDINGS_FILE: &str = "keybindings.json";
+
 // using a macro so that we can automatically generate an iterable vector for bindings. This beats reflection :)
 macro_rules! generate_keybindings {
   ($($field:ident),+) => {
@@ -14,6 +18,20 @@ macro_rules! generate_keybindings {
             $(&self.$field),+
         ]
       }
+
+      /// the bindings by action name, like `copy_to_clipboard`, in the order of the help
+      pub fn actions(&self) -> Vec<(&'static str, &KeyBinding)> {
+        vec![
+            $((stringify!($field), &self.$field)),+
+        ]
+      }
+
+      fn get_mut(&mut self, action: &str) -> Option<&mut KeyBinding> {
+        match action {
+          $(stringify!($field) => Some(&mut self.$field),)+
+          _ => None,
+        }
+      }
     }
   };
 }
@@ -25,18 +43,57 @@ generate_keybindings! {
   help,
   refresh,
   toggle_theme,
+  show_action_log,
   cycle_main_views,
   jump_to_decoder,
   jump_to_encoder,
+  jump_to_settings,
+  swap_sign_verify,
+  edit_in_editor,
   copy_to_clipboard,
+  copy_url_decoded,
   pg_up,
   pg_down,
+  scroll_to_top,
+  scroll_to_bottom,
   up,
   down,
   left,
   right,
   toggle_utc_dates,
   toggle_ignore_exp,
+  next_issue,
+  quit_printing_json,
+  toggle_header_only,
+  toggle_colored_segments,
+  toggle_payload_table,
+  toggle_indent_guides,
+  toggle_claim_types,
+  toggle_aligned_keys,
+  cycle_key_order,
+  expand_arrays,
+  expand_all_arrays,
+  cut_all_arrays,
+  toggle_compact,
+  cycle_split,
+  toggle_raw_signature,
+  toggle_signature_check,
+  toggle_signing_input,
+  compare_signature,
+  pin_alg,
+  inspect_bytes,
+  inspect_claim,
+  explain_token,
+  watch_clipboard,
+  open_in_web_decoder,
+  filter_payload,
+  search,
+  next_match,
+  prev_match,
+  edit_claim,
+  pick_recent_secret,
+  pick_profile,
+  clear_decoder,
   toggle_input_edit,
   clear_input,
   delete_prev_char,
@@ -65,6 +122,18 @@ impl fmt::Display for HContext {
   }
 }
 
+impl HContext {
+  /// whether keys of both contexts can be pressed at the same time. Keys of the decoder are
+  /// handled along the general ones, editable keys only while editing
+  fn overlaps(self, other: HContext) -> bool {
+    self == other
+      || matches!(
+        (self, other),
+        (HContext::General, HContext::Decoder) | (HContext::Decoder, HContext::General)
+      )
+  }
+}
+
 #[derive(Clone)]
 pub struct KeyBinding {
   pub key: Key,
@@ -73,6 +142,100 @@ pub struct KeyBinding {
   pub context: HContext,
 }
 
+impl KeyBinding {
+  /// `key` is the key of the binding or its alternative
+  pub fn matches(&self, key: Key) -> bool {
+    self.key == key || self.alt == Some(key)
+  }
+
+  /// [`KeyBinding::matches`] for a key pressed while typing in an input, where characters are
+  /// text rather than keys of the binding
+  pub fn matches_typing(&self, key: Key) -> bool {
+    !matches!(key, Key::Char(_)) && self.matches(key)
+  }
+
+  fn keys(&self) -> impl Iterator<Item = Key> {
+    [self.key].into_iter().chain(self.alt)
+  }
+}
+
+/// The keys of an action in the keybindings file, a key like `"y"` or a key and its alternative
+/// like `["Ctrl+c", "q"]`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum KeyRemap {
+  Key(String),
+  WithAlt(String, String),
+}
+
+static KEY_BINDINGS: OnceLock<KeyBindings> = OnceLock::new();
+
+/// the keybindings the keys are dispatched with, [`DEFAULT_KEYBINDING`] unless remapped at startup
+pub fn key_bindings() -> &'static KeyBindings {
+  KEY_BINDINGS.get().unwrap_or(&DEFAULT_KEYBINDING)
+}
+
+/// uses `bindings` from now on, only the first call has an effect
+pub fn set_key_bindings(bindings: KeyBindings) {
+  let _ = KEY_BINDINGS.set(bindings);
+}
+
+/// [`DEFAULT_KEYBINDING`] with the keys of the actions in `remaps` replaced, like
+/// `{"copy_to_clipboard": "y"}`. Unknown actions and keys, and keys bound twice where both can be
+/// pressed, are errors
+pub fn remap_key_bindings(remaps: &BTreeMap<String, KeyRemap>) -> Result<KeyBindings, String> {
+  let mut bindings = DEFAULT_KEYBINDING;
+  for (action, remap) in remaps {
+    let binding = bindings
+      .get_mut(action)
+      .ok_or_else(|| format!("Keybindings: there is no action `{action}`"))?;
+    let parse = |key: &str| {
+      key
+        .parse::<Key>()
+        .map_err(|e| format!("Keybindings: {action}: {e}"))
+    };
+    (binding.key, binding.alt) = match remap {
+      KeyRemap::Key(key) => (parse(key)?, None),
+      KeyRemap::WithAlt(key, alt) => (parse(key)?, Some(parse(alt)?)),
+    };
+  }
+  let conflicts = key_conflicts(&bindings);
+  if !conflicts.is_empty() {
+    return Err(format!("Keybindings: {}", conflicts.join(", ")));
+  }
+  Ok(bindings)
+}
+
+/// keys bound to two actions that can be pressed at the same time, like
+/// `` `<y>` is bound to both `quit` and `copy_to_clipboard` ``
+fn key_conflicts(bindings: &KeyBindings) -> Vec<String> {
+  let actions = bindings.actions();
+  let mut conflicts = vec![];
+  for (i, (action, binding)) in actions.iter().enumerate() {
+    for (other_action, other) in &actions[i + 1..] {
+      if !binding.context.overlaps(other.context) {
+        continue;
+      }
+      if let Some(key) = binding.keys().find(|key| other.matches(*key)) {
+        conflicts.push(format!(
+          "`{key}` is bound to both `{action}` and `{other_action}`"
+        ));
+      }
+    }
+  }
+  conflicts
+}
+
+/// reads the keybindings remapped in the keybindings file, a missing file keeps the defaults
+pub fn load_key_bindings(path: &Path) -> Result<KeyBindings, String> {
+  let Ok(content) = fs::read(path) else {
+    return Ok(DEFAULT_KEYBINDING);
+  };
+  let remaps = serde_json::from_slice(&content)
+    .map_err(|e| format!("Keybindings: unable to read {}: {e}", path.display()))?;
+  remap_key_bindings(&remaps)
+}
+
 pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
   quit: KeyBinding {
     key: Key::Ctrl('c'),
@@ -92,6 +255,12 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     desc: "Help page",
     context: HContext::General,
   },
+  show_action_log: KeyBinding {
+    key: Key::Char('L'),
+    alt: None,
+    desc: "Show the action log (<ctrl+d> clears it)",
+    context: HContext::General,
+  },
   refresh: KeyBinding {
     key: Key::Ctrl('r'),
     alt: None,
@@ -100,8 +269,8 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
   },
   toggle_theme: KeyBinding {
     key: Key::Char('t'),
-    alt: None,
-    desc: "Toggle theme",
+    alt: Some(Key::Ctrl('t')),
+    desc: "Toggle light/dark theme",
     context: HContext::General,
   },
   jump_to_decoder: KeyBinding {
@@ -116,6 +285,24 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     desc: "Switch to encoder view",
     context: HContext::General,
   },
+  jump_to_settings: KeyBinding {
+    key: Key::Char('S'),
+    alt: None,
+    desc: "Switch to settings view, <enter> toggles or edits the selected setting",
+    context: HContext::General,
+  },
+  swap_sign_verify: KeyBinding {
+    key: Key::Char('w'),
+    alt: None,
+    desc: "Swap between verifying in the decoder and signing in the encoder, carrying the secret over",
+    context: HContext::General,
+  },
+  edit_in_editor: KeyBinding {
+    key: Key::Char('V'),
+    alt: None,
+    desc: "Edit the selected token, secret, header or payload in $VISUAL or $EDITOR",
+    context: HContext::General,
+  },
   cycle_main_views: KeyBinding {
     key: Key::Tab,
     alt: None,
@@ -125,9 +312,15 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
   copy_to_clipboard: KeyBinding {
     key: Key::Char('c'),
     alt: None,
-    desc: "Copy content to clipboard",
+    desc: "Copy content to clipboard, from a menu in the decoder",
     context: HContext::General,
   },
+  copy_url_decoded: KeyBinding {
+    key: Key::Char('C'),
+    alt: None,
+    desc: "Copy the value of the selected claim URL-decoded",
+    context: HContext::Decoder,
+  },
   down: KeyBinding {
     key: Key::Down,
     alt: Some(Key::Char('j')),
@@ -152,6 +345,18 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     desc: "Scroll page down",
     context: HContext::General,
   },
+  scroll_to_top: KeyBinding {
+    key: Key::Char('g'),
+    alt: None,
+    desc: "Scroll to top with <gg> (requires --vim-keys)",
+    context: HContext::General,
+  },
+  scroll_to_bottom: KeyBinding {
+    key: Key::Char('G'),
+    alt: None,
+    desc: "Scroll to bottom (requires --vim-keys)",
+    context: HContext::General,
+  },
   left: KeyBinding {
     key: Key::Left,
     alt: Some(Key::Char('h')),
@@ -176,6 +381,198 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     desc: "Toggle ignoring exp claim from validation",
     context: HContext::Decoder,
   },
+  next_issue: KeyBinding {
+    key: Key::Char('!'),
+    alt: None,
+    desc: "Jump to the next issue with the token, highlighting its claim",
+    context: HContext::Decoder,
+  },
+  quit_printing_json: KeyBinding {
+    key: Key::Ctrl('p'),
+    alt: None,
+    desc: "Quit and print the decoded token as JSON",
+    context: HContext::Decoder,
+  },
+  toggle_header_only: KeyBinding {
+    key: Key::Char('H'),
+    alt: None,
+    desc: "Toggle decoding only the header, e.g. of JWEs",
+    context: HContext::Decoder,
+  },
+  toggle_colored_segments: KeyBinding {
+    key: Key::Char('M'),
+    alt: None,
+    desc: "Toggle coloring the header, payload and signature segments of the encoded token",
+    context: HContext::Decoder,
+  },
+  toggle_payload_table: KeyBinding {
+    key: Key::Char('v'),
+    alt: None,
+    desc: "Toggle payload view between JSON and claims table",
+    context: HContext::Decoder,
+  },
+  toggle_indent_guides: KeyBinding {
+    key: Key::Char('I'),
+    alt: None,
+    desc: "Toggle indent guides in the payload JSON",
+    context: HContext::Decoder,
+  },
+  toggle_claim_types: KeyBinding {
+    key: Key::Char('T'),
+    alt: None,
+    desc: "Toggle the type and length column in the claims table",
+    context: HContext::Decoder,
+  },
+  toggle_aligned_keys: KeyBinding {
+    key: Key::Char('A'),
+    alt: None,
+    desc: "Toggle right aligned claim keys sized to the longest one in the claims table",
+    context: HContext::Decoder,
+  },
+  cycle_key_order: KeyBinding {
+    key: Key::Char('o'),
+    alt: None,
+    desc: "Cycle the key order of the header and payload: as in the token, alphabetical, RFC",
+    context: HContext::Decoder,
+  },
+  expand_arrays: KeyBinding {
+    key: Key::Char('a'),
+    alt: None,
+    desc: "Show the long arrays of the selected claim in full or cut them again, of all claims in the JSON view",
+    context: HContext::Decoder,
+  },
+  expand_all_arrays: KeyBinding {
+    key: Key::Char('+'),
+    alt: None,
+    desc: "Show the long arrays of all claims in full",
+    context: HContext::Decoder,
+  },
+  cut_all_arrays: KeyBinding {
+    key: Key::Char('-'),
+    alt: None,
+    desc: "Cut the long arrays of all claims",
+    context: HContext::Decoder,
+  },
+  toggle_compact: KeyBinding {
+    key: Key::Char('z'),
+    alt: None,
+    desc: "Toggle compact mode hiding the signature block",
+    context: HContext::Decoder,
+  },
+  cycle_split: KeyBinding {
+    key: Key::Char('|'),
+    alt: None,
+    desc: "Cycle the split between the token and the decoded blocks: 50/50, 30/70, 70/30",
+    context: HContext::Decoder,
+  },
+  toggle_raw_signature: KeyBinding {
+    key: Key::Char('s'),
+    alt: None,
+    desc: "Toggle showing the raw signature in the signature block",
+    context: HContext::Decoder,
+  },
+  toggle_signature_check: KeyBinding {
+    key: Key::Char('x'),
+    alt: None,
+    desc: "Toggle the dry-run check of the expected vs actual signature in the signature block",
+    context: HContext::Decoder,
+  },
+  toggle_signing_input: KeyBinding {
+    key: Key::Char('X'),
+    alt: None,
+    desc: "Toggle the signing input the signature covers in the signature block",
+    context: HContext::Decoder,
+  },
+  compare_signature: KeyBinding {
+    key: Key::Char('R'),
+    alt: None,
+    desc: "Compare the signature with a reference signature pasted as base64url or hex",
+    context: HContext::Decoder,
+  },
+  pin_alg: KeyBinding {
+    key: Key::Char('P'),
+    alt: None,
+    desc: "Pin the alg every token is verified with for the session, empty to unpin",
+    context: HContext::Decoder,
+  },
+  inspect_bytes: KeyBinding {
+    key: Key::Char('b'),
+    alt: None,
+    desc: "Inspect the bytes of the selected claim or the focused token segment as a hexdump",
+    context: HContext::Decoder,
+  },
+  inspect_claim: KeyBinding {
+    key: Key::Char('p'),
+    alt: None,
+    desc: "Peek at the token in the selected claim, decoded and verified on its own",
+    context: HContext::Decoder,
+  },
+  explain_token: KeyBinding {
+    key: Key::Char('W'),
+    alt: None,
+    desc: "Explain the token in plain English, from its header and standard claims",
+    context: HContext::Decoder,
+  },
+  watch_clipboard: KeyBinding {
+    key: Key::Char('Y'),
+    alt: None,
+    desc: "Watch the clipboard and decode tokens copied anywhere, press again to stop",
+    context: HContext::Decoder,
+  },
+  open_in_web_decoder: KeyBinding {
+    key: Key::Char('O'),
+    alt: None,
+    desc: "Open the token in the web decoder set with --web-decoder-url, after confirming",
+    context: HContext::Decoder,
+  },
+  filter_payload: KeyBinding {
+    key: Key::Char('/'),
+    alt: None,
+    desc: "Filter payload claims by key",
+    context: HContext::Decoder,
+  },
+  search: KeyBinding {
+    key: Key::Char('f'),
+    alt: None,
+    desc: "Find text in the header and payload",
+    context: HContext::Decoder,
+  },
+  next_match: KeyBinding {
+    key: Key::Char('n'),
+    alt: None,
+    desc: "Jump to the next match of the search",
+    context: HContext::Decoder,
+  },
+  prev_match: KeyBinding {
+    key: Key::Char('N'),
+    alt: None,
+    desc: "Jump to the previous match of the search",
+    context: HContext::Decoder,
+  },
+  edit_claim: KeyBinding {
+    key: Key::Char('m'),
+    alt: None,
+    desc: "Set the claim selected in the claims table to a JSON value",
+    context: HContext::Decoder,
+  },
+  pick_recent_secret: KeyBinding {
+    key: Key::Char('K'),
+    alt: None,
+    desc: "Pick a recently used key file as the secret",
+    context: HContext::Decoder,
+  },
+  pick_profile: KeyBinding {
+    key: Key::Char('U'),
+    alt: None,
+    desc: "Pick a profile of the profiles file, switching the key and the checks at once",
+    context: HContext::Decoder,
+  },
+  clear_decoder: KeyBinding {
+    key: Key::Ctrl('l'),
+    alt: None,
+    desc: "Clear token, secret and decoded data",
+    context: HContext::Decoder,
+  },
   toggle_input_edit: KeyBinding {
     key: Key::Enter,
     alt: Some(Key::Char('e')),
@@ -250,16 +647,75 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
   },
 };
 
+/// An action a block offers in the hint of its title. The key comes from the keybindings above
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockAction {
+  Edit,
+  Copy,
+  StopEditing,
+  ClearInput,
+  Find,
+  NextMatch,
+  PrevMatch,
+}
+
+impl BlockAction {
+  pub fn key(&self) -> Key {
+    match self {
+      BlockAction::Edit | BlockAction::Find => key_bindings().toggle_input_edit.key,
+      BlockAction::Copy => key_bindings().copy_to_clipboard.key,
+      BlockAction::StopEditing => key_bindings().esc.key,
+      BlockAction::ClearInput => key_bindings().clear_input.key,
+      BlockAction::NextMatch => key_bindings().next_match.key,
+      BlockAction::PrevMatch => key_bindings().prev_match.key,
+    }
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      BlockAction::Edit => "edit",
+      BlockAction::Copy => "copy",
+      BlockAction::StopEditing => "stop editing",
+      BlockAction::ClearInput => "clear",
+      BlockAction::Find => "find",
+      BlockAction::NextMatch => "next",
+      BlockAction::PrevMatch => "previous",
+    }
+  }
+}
+
+/// `key` as the hints show it, in lower case like `<ctrl+d>` or `<tab>`, except for the letters
+/// themselves
+pub fn key_label(key: Key) -> String {
+  match key {
+    Key::Char(c) => format!("<{c}>"),
+    Key::Ctrl(c) => format!("<ctrl+{c}>"),
+    key => key.to_string().to_lowercase(),
+  }
+}
+
+/// e.g. `(<enter> edit | <c> copy) `, empty without actions
+pub fn hint(actions: &[BlockAction]) -> String {
+  if actions.is_empty() {
+    return String::new();
+  }
+  let actions = actions
+    .iter()
+    .map(|action| format!("{} {}", key_label(action.key()), action.label()))
+    .collect::<Vec<_>>();
+  format!("({}) ", actions.join(" | "))
+}
+
 pub fn get_help_docs() -> Vec<Vec<String>> {
-  let items = DEFAULT_KEYBINDING.as_iter();
+  let items = key_bindings().as_iter();
 
   items.iter().map(|it| help_row(it)).collect()
 }
 
 fn help_row(item: &KeyBinding) -> Vec<String> {
   vec![
-    if item.alt.is_some() {
-      format!("{} | {}", item.key, item.alt.unwrap())
+    if let Some(alt) = item.alt {
+      format!("{} | {}", item.key, alt)
     } else {
       item.key.to_string()
     },
@@ -270,10 +726,69 @@ fn help_row(item: &KeyBinding) -> Vec<String> {
 
 #[cfg(test)]
 mod tests {
-  use super::DEFAULT_KEYBINDING;
+  use super::*;
 
   #[test]
   fn test_as_iter() {
-    assert!(DEFAULT_KEYBINDING.as_iter().len() >= 28);
+    assert!(key_bindings().as_iter().len() >= 28);
+  }
+
+  #[test]
+  fn test_remap_key_bindings() {
+    let remaps = |json: &str| remap_key_bindings(&serde_json::from_str(json).unwrap());
+    let bindings = remaps(r#"{"copy_to_clipboard": "y", "quit": ["Ctrl+c", "Q"]}"#).unwrap();
+    assert_eq!(bindings.copy_to_clipboard.key, Key::Char('y'));
+    assert_eq!(bindings.copy_to_clipboard.alt, None);
+    assert!(bindings.quit.matches(Key::Char('Q')));
+    assert!(!bindings.quit.matches(Key::Char('q')));
+    assert_eq!(bindings.help.key, Key::Char('?'));
+
+    assert_eq!(
+      remaps(r#"{"copy": "y"}"#).err(),
+      Some("Keybindings: there is no action `copy`".into())
+    );
+    assert_eq!(
+      remaps(r#"{"help": "Shift+h"}"#).err(),
+      Some("Keybindings: help: `Shift+h` is not a key like `c`, `Ctrl+d`, `Enter` or `F5`".into())
+    );
+    // the decoder's keys are pressed along the general ones, editable keys aren't
+    assert_eq!(
+      remaps(r#"{"toggle_utc_dates": "?"}"#).err(),
+      Some("Keybindings: `<?>` is bound to both `help` and `toggle_utc_dates`".into())
+    );
+    assert!(remaps(r#"{"delete_till_end": "Ctrl+l"}"#).is_ok());
+  }
+
+  #[test]
+  fn test_key_binding_matches() {
+    let binding = &DEFAULT_KEYBINDING.toggle_input_edit;
+    assert!(binding.matches(Key::Enter));
+    assert!(binding.matches(Key::Char('e')));
+    assert!(!binding.matches(Key::Char('c')));
+    // `e` is typed into the input
+    assert!(binding.matches_typing(Key::Enter));
+    assert!(!binding.matches_typing(Key::Char('e')));
+  }
+
+  #[test]
+  fn test_default_key_bindings_have_no_conflicts() {
+    assert_eq!(key_conflicts(&DEFAULT_KEYBINDING), Vec::<String>::new());
+  }
+
+  #[test]
+  fn test_hint() {
+    assert_eq!(
+      hint(&[BlockAction::Edit, BlockAction::Copy]),
+      "(<enter> edit | <c> copy) "
+    );
+    assert_eq!(
+      hint(&[BlockAction::StopEditing, BlockAction::ClearInput]),
+      "(<esc> stop editing | <ctrl+d> clear) "
+    );
+    assert_eq!(
+      hint(&[BlockAction::NextMatch, BlockAction::PrevMatch]),
+      "(<n> next | <N> previous) "
+    );
+    assert_eq!(hint(&[]), "");
   }
 }
@@ -0,0 +1,186 @@
+//! Where the key a token is verified with comes from and what kind of key it is, shown in the
+//! status bar. Secrets themselves are never part of it
+
+use std::str::from_utf8;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::Value;
+
+use super::{
+  certificate::{der_element, Certificate},
+  private_key::is_private_key,
+  utils::{
+    decode_hex, ec_curve, is_jwks_text, pem_to_der, slurp_file, strip_leading_symbol, B64_PREFIX,
+    HEX_PREFIX, JWKS_PREFIX,
+  },
+};
+
+const SEQUENCE: u8 = 0x30;
+const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+const OCTET_STRING: u8 = 0x04;
+/// DER encoded object identifier of Ed25519 keys
+const ED25519_OID: [u8; 5] = [0x06, 0x03, 0x2b, 0x65, 0x70];
+
+/// e.g. `key: @keys/public.pem RSA-2048` or `key: HMAC secret (raw text, 24 bytes, masked)`, `None`
+/// without secret
+pub fn key_source(secret: &str) -> Option<String> {
+  if secret.is_empty() {
+    return None;
+  }
+  let secret = secret.strip_prefix(JWKS_PREFIX).unwrap_or(secret);
+  if !secret.starts_with('@') {
+    let kind = if is_jwks_text(secret) {
+      jwks_kind(secret)
+    } else {
+      hmac_secret_kind(secret)
+    };
+    return Some(format!("key: {kind}"));
+  }
+  let path = strip_leading_symbol(secret);
+  let name = if path == "-" {
+    "STDIN".into()
+  } else {
+    format!("@{path}")
+  };
+  // streamed secrets were read by the decode already and come from the cache
+  let kind = match slurp_file(path) {
+    Ok(content) => key_kind(&content),
+    Err(_) => "unreadable".into(),
+  };
+  Some(format!("key: {name} {kind}"))
+}
+
+/// how an inline HMAC secret is read per its prefix and the bytes it makes, so that a base64
+/// secret used as text stands out, like `HMAC secret (raw text, 24 bytes, masked)`
+fn hmac_secret_kind(secret: &str) -> String {
+  let (encoding, bytes) = if let Some(b64) = secret.strip_prefix(B64_PREFIX) {
+    ("base64", STANDARD.decode(b64).ok().map(|bytes| bytes.len()))
+  } else if let Some(hex) = secret.strip_prefix(HEX_PREFIX) {
+    ("hex", decode_hex(hex.trim()).map(|bytes| bytes.len()))
+  } else {
+    ("raw text", Some(secret.len()))
+  };
+  match bytes {
+    Some(1) => format!("HMAC secret ({encoding}, 1 byte, masked)"),
+    Some(bytes) => format!("HMAC secret ({encoding}, {bytes} bytes, masked)"),
+    None => format!("HMAC secret (invalid {encoding})"),
+  }
+}
+
+/// kind of key in a secret file, like `EC P-256` or `X.509 RSA-2048` for certificates
+fn key_kind(content: &[u8]) -> String {
+  let text = from_utf8(content).ok();
+  if let Some(text) = text.filter(|text| is_jwks_text(text)) {
+    return jwks_kind(text);
+  }
+  let pem = pem_to_der(content);
+  let der = pem.as_deref().unwrap_or(content);
+  let algorithm = if let Some(curve) = ec_curve(der) {
+    format!("EC {curve}")
+  } else if der.windows(ED25519_OID.len()).any(|w| w == ED25519_OID) {
+    "Ed25519".into()
+  } else if let Some(bits) = rsa_modulus_bits(der) {
+    format!("RSA-{bits}")
+  } else if pem.is_some() {
+    "PEM of unknown key type".into()
+  } else if text.is_some() {
+    return "HMAC secret".into();
+  } else {
+    return "DER of unknown key type".into();
+  };
+  if Certificate::from_der(der).is_some() {
+    format!("X.509 {algorithm}")
+  } else if is_private_key(content) {
+    format!("{algorithm} private key, verified with its public key")
+  } else {
+    algorithm
+  }
+}
+
+fn jwks_kind(text: &str) -> String {
+  let keys = serde_json::from_str::<Value>(text)
+    .ok()
+    .and_then(|jwks| Some(jwks.get("keys")?.as_array()?.len()))
+    .unwrap_or_default();
+  match keys {
+    1 => "JWKS (1 key)".into(),
+    _ => format!("JWKS ({keys} keys)"),
+  }
+}
+
+/// size of the modulus of a DER encoded RSA key (SPKI, PKCS#1, PKCS#8 or in a certificate), the
+/// first INTEGER too large to be anything else
+fn rsa_modulus_bits(der: &[u8]) -> Option<usize> {
+  let (tag, content, rest) = der_element(der)?;
+  let found = match tag {
+    INTEGER if content.len() >= 64 => {
+      let modulus = &content[content.iter().take_while(|b| **b == 0).count()..];
+      modulus
+        .first()
+        .map(|first| modulus.len() * 8 - first.leading_zeros() as usize)
+    }
+    SEQUENCE | OCTET_STRING => rsa_modulus_bits(content),
+    // the first byte is the number of unused bits
+    BIT_STRING => rsa_modulus_bits(content.get(1..)?),
+    _ => None,
+  };
+  found.or_else(|| rsa_modulus_bits(rest))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_key_source() {
+    assert_eq!(key_source(""), None);
+    assert_eq!(
+      key_source("secret").as_deref(),
+      Some("key: HMAC secret (raw text, 6 bytes, masked)")
+    );
+    assert_eq!(
+      key_source("b64:c2VjcmV0").as_deref(),
+      Some("key: HMAC secret (base64, 6 bytes, masked)")
+    );
+    assert_eq!(
+      key_source("hex:73").as_deref(),
+      Some("key: HMAC secret (hex, 1 byte, masked)")
+    );
+    assert_eq!(
+      key_source("b64:c2VjcmV0!").as_deref(),
+      Some("key: HMAC secret (invalid base64)")
+    );
+    assert_eq!(
+      key_source("hex:7").as_deref(),
+      Some("key: HMAC secret (invalid hex)")
+    );
+
+    for (file, kind) in [
+      ("test_rsa_public_key.pem", "RSA-2048"),
+      ("test_rsa_public_key.der", "RSA-2048"),
+      (
+        "test_rsa_private_key.der",
+        "RSA-2048 private key, verified with its public key",
+      ),
+      ("test_rsa_cert_bundle.crt", "X.509 RSA-2048"),
+      ("test_ecdsa_public_key.pem", "EC P-384"),
+      ("test_ecdsa_p256_public_key.pem", "EC P-256"),
+      ("test_es256k_public_key.pem", "EC secp256k1"),
+      ("test_eddsa_public_key.pem", "Ed25519"),
+      ("test_ecdsa_public_jwks.json", "JWKS (2 keys)"),
+      ("test_hmac_jwks.txt", "JWKS (2 keys)"),
+      ("test_hmac_secrets.txt", "HMAC secret"),
+      ("nonexistent.pem", "unreadable"),
+    ] {
+      assert_eq!(
+        key_source(&format!("@./test_data/{file}")),
+        Some(format!("key: @./test_data/{file} {kind}"))
+      );
+    }
+    assert_eq!(
+      key_source("jwks:@./test_data/test_hmac_jwks.txt").as_deref(),
+      Some("key: @./test_data/test_hmac_jwks.txt JWKS (2 keys)")
+    );
+  }
+}
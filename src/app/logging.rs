@@ -0,0 +1,192 @@
+//! Debug log of what the tool does, to troubleshoot it: decodes, key file reads, clipboard access,
+//! notifications and panics. Written to the `--log-file` through the `log` facade at the level of
+//! `RUST_LOG`. Tokens and secrets are never logged, only their fingerprints and lengths, and
+//! anything looking like a token that slips into a message is redacted
+
+use std::{
+  fs::{File, OpenOptions},
+  io::Write,
+  path::Path,
+  sync::Mutex,
+};
+
+use chrono::Utc;
+use log::{info, LevelFilter, Log, Metadata, Record};
+
+use super::utils::token_fingerprint;
+
+/// level logged at when `RUST_LOG` isn't set
+pub const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Debug;
+
+/// targets of the library and the binary, the dependencies' own logs are left out
+const TARGETS: [&str; 2] = ["jwt_ui", "jwtui"];
+
+struct FileLogger {
+  file: Mutex<File>,
+  level: LevelFilter,
+}
+
+impl Log for FileLogger {
+  fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+    metadata.level() <= self.level && TARGETS.iter().any(|t| metadata.target().starts_with(t))
+  }
+
+  fn log(&self, record: &Record<'_>) {
+    if !self.enabled(record.metadata()) {
+      return;
+    }
+    let line = format!(
+      "{} {:<5} {}: {}\n",
+      Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+      record.level(),
+      record.target(),
+      redact_tokens(&record.args().to_string())
+    );
+    let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+    // a log that can't be written is no reason to disturb the UI
+    let _ = file.write_all(line.as_bytes());
+  }
+
+  fn flush(&self) {
+    let _ = self.file.lock().unwrap_or_else(|e| e.into_inner()).flush();
+  }
+}
+
+/// the level of a `RUST_LOG` like `debug` or `jwt_ui=trace,warn`. A directive for this crate wins
+/// over a bare level, those for other crates are ignored
+pub fn parse_log_level(spec: &str) -> Result<LevelFilter, String> {
+  let mut level = None;
+  for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+    let (target, value) = match directive.split_once('=') {
+      Some((target, value)) => (Some(target.trim()), value.trim()),
+      None => (None, directive),
+    };
+    let parsed = value
+      .parse::<LevelFilter>()
+      .map_err(|_| format!("RUST_LOG: `{value}` is not a log level like `debug` or `trace`"))?;
+    match target {
+      Some(target) if TARGETS.contains(&target) => return Ok(parsed),
+      Some(_) => {}
+      None => level = Some(parsed),
+    }
+  }
+  Ok(level.unwrap_or(DEFAULT_LOG_LEVEL))
+}
+
+/// appends the log to `path` at the level of `rust_log`, [`DEFAULT_LOG_LEVEL`] when it's not set
+pub fn init_logging(path: &Path, rust_log: Option<&str>) -> Result<(), String> {
+  let level = match rust_log.filter(|spec| !spec.trim().is_empty()) {
+    Some(spec) => parse_log_level(spec)?,
+    None => DEFAULT_LOG_LEVEL,
+  };
+  let file = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)
+    .map_err(|e| format!("Unable to open the log file {}: {e}", path.display()))?;
+  log::set_boxed_logger(Box::new(FileLogger {
+    file: Mutex::new(file),
+    level,
+  }))
+  .map_err(|e| e.to_string())?;
+  log::set_max_level(level);
+  info!("jwtui {} started", env!("CARGO_PKG_VERSION"));
+  Ok(())
+}
+
+/// `text` with the tokens in it, runs of base64url and dots starting like a JSON header, replaced
+/// by their fingerprint like `<token 1a2b3c4d>`
+pub fn redact_tokens(text: &str) -> String {
+  let is_token_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.';
+  let mut redacted = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find("eyJ") {
+    redacted.push_str(&rest[..start]);
+    let candidate = &rest[start..];
+    let end = candidate
+      .find(|c: char| !is_token_char(c))
+      .unwrap_or(candidate.len());
+    let token = &candidate[..end];
+    if token.matches('.').count() >= 2 {
+      redacted.push_str(&format!("<token {}>", token_fingerprint(token)));
+    } else {
+      redacted.push_str(token);
+    }
+    rest = &candidate[end..];
+  }
+  redacted.push_str(rest);
+  redacted
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use log::Level;
+
+  use super::*;
+
+  #[test]
+  fn test_parse_log_level() {
+    assert_eq!(parse_log_level("trace"), Ok(LevelFilter::Trace));
+    assert_eq!(parse_log_level("warn,jwt_ui=trace"), Ok(LevelFilter::Trace));
+    assert_eq!(parse_log_level("ratatui=trace,info"), Ok(LevelFilter::Info));
+    assert_eq!(parse_log_level("ratatui=trace"), Ok(DEFAULT_LOG_LEVEL));
+    assert_eq!(
+      parse_log_level("loud"),
+      Err("RUST_LOG: `loud` is not a log level like `debug` or `trace`".into())
+    );
+  }
+
+  #[test]
+  fn test_redact_tokens() {
+    let token = "eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.c2ln";
+    assert_eq!(
+      redact_tokens(&format!("pasted `{token}` from the clipboard")),
+      format!(
+        "pasted `<token {}>` from the clipboard",
+        token_fingerprint(token)
+      )
+    );
+    // a lone segment isn't a token
+    assert_eq!(
+      redact_tokens("header eyJhbGciOiJub25lIn0"),
+      "header eyJhbGciOiJub25lIn0"
+    );
+  }
+
+  #[test]
+  fn test_file_logger() {
+    let path = std::env::temp_dir().join("jwt-ui-test.log");
+    let logger = FileLogger {
+      file: Mutex::new(File::create(&path).unwrap()),
+      level: LevelFilter::Info,
+    };
+    let log = |level, target, message: &str| {
+      logger.log(
+        &Record::builder()
+          .level(level)
+          .target(target)
+          .args(format_args!("{message}"))
+          .build(),
+      )
+    };
+    log(
+      Level::Info,
+      "jwt_ui::app",
+      "decoded eyJhbGciOiJub25lIn0.e30.",
+    );
+    log(Level::Debug, "jwt_ui::app", "too verbose");
+    log(Level::Info, "copypasta", "not ours");
+    logger.flush();
+
+    let content = fs::read_to_string(&path).unwrap();
+    let lines = content.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].ends_with(&format!(
+      " INFO  jwt_ui::app: decoded <token {}>",
+      token_fingerprint("eyJhbGciOiJub25lIn0.e30.")
+    )));
+    fs::remove_file(&path).unwrap();
+  }
+}
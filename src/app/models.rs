@@ -1,6 +1,16 @@
-use ratatui::{layout::Rect, widgets::TableState, Frame};
+use std::fmt;
 
-use super::{ActiveBlock, App, Route};
+use ratatui::{
+  layout::Rect,
+  widgets::{Paragraph, TableState, Wrap},
+  Frame,
+};
+
+use super::{
+  config::Config,
+  utils::{hexdump, Segment},
+  ActiveBlock, App, Route,
+};
 
 #[allow(dead_code)]
 pub trait AppResource {
@@ -8,17 +18,30 @@ pub trait AppResource {
 }
 
 pub trait Scrollable {
-  fn handle_scroll(&mut self, up: bool, page: bool) {
+  fn handle_scroll(&mut self, up: bool, page: bool, config: &Config) {
     // support page up/down
-    let inc_or_dec = if page { 10 } else { 1 };
+    let inc_or_dec = if page {
+      config.page_step
+    } else {
+      config.scroll_step
+    };
     if up {
       self.scroll_up(inc_or_dec);
     } else {
       self.scroll_down(inc_or_dec);
     }
   }
+  fn handle_scroll_to_edge(&mut self, top: bool) {
+    if top {
+      // offsets are `u16`, so this is as far as scrolling goes
+      self.scroll_up(u16::MAX as usize);
+    } else {
+      self.scroll_to_bottom();
+    }
+  }
   fn scroll_down(&mut self, inc_or_dec: usize);
   fn scroll_up(&mut self, inc_or_dec: usize);
+  fn scroll_to_bottom(&mut self);
 }
 
 #[derive(Clone, Debug)]
@@ -27,6 +50,12 @@ pub struct StatefulTable<T> {
   pub items: Vec<T>,
 }
 
+impl<T> Default for StatefulTable<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
 impl<T> StatefulTable<T> {
   pub fn new() -> StatefulTable<T> {
     StatefulTable {
@@ -44,6 +73,14 @@ impl<T> StatefulTable<T> {
     table
   }
 
+  /// position of the selected item and the item count, e.g. `3/27`, `0/0` without selection
+  pub fn position(&self) -> String {
+    match self.state.selected() {
+      Some(i) if i < self.items.len() => format!("{}/{}", i + 1, self.items.len()),
+      _ => "0/0".into(),
+    }
+  }
+
   pub fn set_items(&mut self, items: Vec<T>) {
     let item_len = items.len();
     self.items = items;
@@ -62,6 +99,16 @@ impl<T> StatefulTable<T> {
   }
 }
 
+impl<T: Clone> StatefulTable<T> {
+  /// returns a copy of the selected item
+  pub fn get_selected_item_copy(&self) -> Option<T> {
+    self
+      .state
+      .selected()
+      .and_then(|i| self.items.get(i).cloned())
+  }
+}
+
 impl<T> Scrollable for StatefulTable<T> {
   fn scroll_down(&mut self, increment: usize) {
     if let Some(i) = self.state.selected() {
@@ -80,6 +127,10 @@ impl<T> Scrollable for StatefulTable<T> {
       }
     }
   }
+
+  fn scroll_to_bottom(&mut self) {
+    self.scroll_down(self.items.len());
+  }
 }
 
 #[derive(Clone)]
@@ -143,6 +194,13 @@ impl BlockState {
   pub fn get_active_block(&self) -> &ActiveBlock {
     &self.items[self.index].active_block
   }
+  /// like comparing with `get_active_block` but `false` without blocks
+  pub fn is_active(&self, block: ActiveBlock) -> bool {
+    self
+      .items
+      .get(self.index)
+      .is_some_and(|route| route.active_block == block)
+  }
   pub fn next(&mut self) {
     self.index = (self.index + 1) % self.items.len();
   }
@@ -158,26 +216,155 @@ impl BlockState {
 #[derive(Debug, Eq, PartialEq, Default)]
 pub struct ScrollableTxt {
   items: Vec<String>,
+  /// first visible row, counted in wrapped rows once the width is known
   pub offset: u16,
+  /// width the text is wrapped in, `None` until it's drawn
+  width: Option<u16>,
 }
 
 impl ScrollableTxt {
   pub fn new(item: String) -> ScrollableTxt {
     let items: Vec<&str> = item.split('\n').collect();
     let items: Vec<String> = items.iter().map(|it| it.to_string()).collect();
-    ScrollableTxt { items, offset: 0 }
+    ScrollableTxt {
+      items,
+      offset: 0,
+      width: None,
+    }
   }
 
   pub fn get_txt(&self) -> String {
     self.items.join("\n")
   }
+
+  /// sets the width the text is wrapped in when drawn, so that scrolling counts the rows long
+  /// lines wrap into. The offset is kept within the rows of the text at that width
+  pub fn set_width(&mut self, width: u16) {
+    self.width = Some(width);
+    self.offset = self.offset.min(self.max_offset());
+  }
+
+  /// rows the text takes up, one per line as long as the width isn't known
+  pub fn row_count(&self) -> usize {
+    self.rows_of(&self.items)
+  }
+
+  /// the row the given line starts at
+  pub fn row_of_line(&self, line: usize) -> u16 {
+    let lines = &self.items[..line.min(self.items.len())];
+    self.rows_of(lines).try_into().unwrap_or(u16::MAX)
+  }
+
+  fn rows_of(&self, lines: &[String]) -> usize {
+    match self.width {
+      // wrapped like the paragraphs showing the text
+      Some(width) => Paragraph::new(lines.join("\n"))
+        .wrap(Wrap { trim: false })
+        .line_count(width),
+      None => lines.len(),
+    }
+  }
+
+  /// as far as `scroll_down` goes, keeping the last rows in view
+  fn max_offset(&self) -> u16 {
+    self.row_count().saturating_sub(3) as u16
+  }
+}
+
+/// hexdump of a claim or token segment, shown in a popup
+#[derive(Debug, Eq, PartialEq, Default)]
+pub struct ByteInspector {
+  /// what is inspected, e.g. `claim name`
+  pub title: String,
+  pub dump: ScrollableTxt,
+}
+
+impl ByteInspector {
+  pub fn new(title: String, bytes: &[u8]) -> Self {
+    ByteInspector {
+      title,
+      dump: ScrollableTxt::new(hexdump(bytes)),
+    }
+  }
+}
+
+/// a claim value decoded as a token of its own, shown in a popup over the decoder
+#[derive(Debug, Eq, PartialEq)]
+pub struct TokenInspector {
+  /// what is inspected, e.g. `claim id_token`
+  pub title: String,
+  /// whether the signature verified, or why the value couldn't be verified or decoded
+  pub verdict: Result<String, String>,
+  /// header and payload JSON
+  pub text: ScrollableTxt,
+}
+
+/// entry of the copy menu of the decoder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyTarget {
+  RawToken,
+  HeaderJson,
+  PayloadJson,
+  /// header and payload as one JSON object, like `--stdout --json`
+  DecodedJson,
+  /// flat payload claims as `KEY=value` lines
+  PayloadEnv,
+  SignatureHex,
+  /// value of the claim with this key
+  ClaimValue(String),
+  Secret,
+  /// base64url segment as it is in the token
+  Segment(Segment),
+  /// `curl` command calling the configured URL with the token as bearer
+  CurlCommand,
+  /// header and payload with the claim edits, encoded again without a signature
+  UnsignedToken,
+}
+
+impl CopyTarget {
+  /// what was copied, for the notification
+  pub fn what(&self) -> String {
+    match self {
+      CopyTarget::RawToken => "token".into(),
+      CopyTarget::HeaderJson => "header".into(),
+      CopyTarget::PayloadJson => "payload".into(),
+      CopyTarget::DecodedJson => "decoded token".into(),
+      CopyTarget::PayloadEnv => "payload as env exports".into(),
+      CopyTarget::SignatureHex => "signature as hex".into(),
+      CopyTarget::ClaimValue(key) => format!("claim '{key}'"),
+      CopyTarget::Secret => "secret".into(),
+      CopyTarget::Segment(segment) => format!("{segment} segment"),
+      CopyTarget::CurlCommand => "curl command".into(),
+      CopyTarget::UnsignedToken => "unsigned token (no signature)".into(),
+    }
+  }
+}
+
+impl fmt::Display for CopyTarget {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      CopyTarget::RawToken => write!(f, "Raw token"),
+      CopyTarget::HeaderJson => write!(f, "Header JSON"),
+      CopyTarget::PayloadJson => write!(f, "Payload JSON"),
+      CopyTarget::DecodedJson => write!(f, "Full decoded JSON"),
+      CopyTarget::PayloadEnv => write!(f, "Payload as .env (KEY=value)"),
+      CopyTarget::SignatureHex => write!(f, "Signature as hex"),
+      CopyTarget::ClaimValue(key) => write!(f, "Value of claim '{key}'"),
+      CopyTarget::Secret => write!(f, "Secret"),
+      CopyTarget::Segment(Segment::Header) => write!(f, "Header segment (base64url)"),
+      CopyTarget::Segment(Segment::Payload) => write!(f, "Payload segment (base64url)"),
+      CopyTarget::Segment(Segment::Signature) => write!(f, "Signature segment (base64url)"),
+      CopyTarget::CurlCommand => write!(f, "curl command with the token"),
+      CopyTarget::UnsignedToken => write!(f, "Unsigned token of the shown claims (not signed)"),
+    }
+  }
 }
 
 impl Scrollable for ScrollableTxt {
   fn scroll_down(&mut self, increment: usize) {
     // scroll only if offset is less than total lines in text
     // we subtract increment + 2 to keep the text in view. Its just an arbitrary number that works
-    if self.offset < self.items.len().saturating_sub(increment + 2) as u16 {
+    if self.offset < self.row_count().saturating_sub(increment + 2) as u16 {
       self.offset += increment as u16;
     }
   }
@@ -187,6 +374,10 @@ impl Scrollable for ScrollableTxt {
       self.offset = self.offset.saturating_sub(decrement as u16);
     }
   }
+
+  fn scroll_to_bottom(&mut self) {
+    self.offset = self.max_offset();
+  }
 }
 
 #[cfg(test)]
@@ -240,29 +431,52 @@ mod tests {
     assert_eq!(sft2.state.selected(), Some(0));
   }
 
+  #[test]
+  fn test_stateful_table_get_selected_item_copy() {
+    let mut sft = StatefulTable::with_items(vec!["A", "B"]);
+    assert_eq!(sft.get_selected_item_copy(), Some("A"));
+    sft.scroll_down(1);
+    assert_eq!(sft.get_selected_item_copy(), Some("B"));
+
+    let sft: StatefulTable<&str> = StatefulTable::new();
+    assert_eq!(sft.get_selected_item_copy(), None);
+  }
+
+  #[test]
+  fn test_stateful_table_position() {
+    let mut table: StatefulTable<&str> = StatefulTable::new();
+    assert_eq!(table.position(), "0/0");
+
+    table.set_items(vec!["A", "B", "C"]);
+    assert_eq!(table.position(), "1/3");
+    table.scroll_to_bottom();
+    assert_eq!(table.position(), "3/3");
+  }
+
   #[test]
   fn test_handle_table_scroll() {
+    let config = Config::default();
     let mut item: StatefulTable<&str> = StatefulTable::new();
     item.set_items(vec!["A", "B", "C"]);
 
     assert_eq!(item.state.selected(), Some(0));
 
-    item.handle_scroll(false, false);
+    item.handle_scroll(false, false, &config);
     assert_eq!(item.state.selected(), Some(1));
 
-    item.handle_scroll(false, false);
+    item.handle_scroll(false, false, &config);
     assert_eq!(item.state.selected(), Some(2));
 
-    item.handle_scroll(false, false);
+    item.handle_scroll(false, false, &config);
     assert_eq!(item.state.selected(), Some(2));
     // previous
-    item.handle_scroll(true, false);
+    item.handle_scroll(true, false, &config);
     assert_eq!(item.state.selected(), Some(1));
     // page down
-    item.handle_scroll(false, true);
+    item.handle_scroll(false, true, &config);
     assert_eq!(item.state.selected(), Some(2));
     // page up
-    item.handle_scroll(true, true);
+    item.handle_scroll(true, true, &config);
     assert_eq!(item.state.selected(), Some(0));
   }
 
@@ -338,4 +552,50 @@ mod tests {
     // no overflow past (0)
     assert_eq!(stxt2.offset, 0);
   }
+
+  #[test]
+  fn test_scrollable_txt_wrapped() {
+    // the first line wraps into 3 rows of 10 columns
+    let mut stxt = ScrollableTxt::new(format!("{}\na\nb\nc", "x".repeat(25)));
+    assert_eq!(stxt.row_count(), 4);
+    stxt.handle_scroll_to_edge(false);
+    assert_eq!(stxt.offset, 1);
+
+    stxt.set_width(10);
+    assert_eq!(stxt.row_count(), 6);
+    assert_eq!(stxt.row_of_line(1), 3);
+    assert_eq!(stxt.row_of_line(9), 6);
+    stxt.handle_scroll_to_edge(false);
+    assert_eq!(stxt.offset, 3);
+    stxt.scroll_down(1);
+    assert_eq!(stxt.offset, 3);
+    stxt.scroll_up(3);
+    stxt.scroll_down(2);
+    assert_eq!(stxt.offset, 2);
+
+    // words are wrapped as a whole, like the paragraph shows them
+    let mut words = ScrollableTxt::new("aaaa bbbb cccc".into());
+    words.set_width(9);
+    assert_eq!(words.row_count(), 2);
+
+    // a wider view has fewer rows, the offset stays within them
+    stxt.handle_scroll_to_edge(false);
+    stxt.set_width(30);
+    assert_eq!(stxt.offset, 1);
+  }
+
+  #[test]
+  fn test_handle_scroll_to_edge() {
+    let mut stxt = ScrollableTxt::new("te\nst\nmul\ntil\ni\nne\nstr\ni\nn\ng".into());
+    stxt.handle_scroll_to_edge(false);
+    assert_eq!(stxt.offset, 7);
+    stxt.handle_scroll_to_edge(true);
+    assert_eq!(stxt.offset, 0);
+
+    let mut sft = StatefulTable::with_items(vec!["a", "b", "c"]);
+    sft.handle_scroll_to_edge(false);
+    assert_eq!(sft.state.selected(), Some(2));
+    sft.handle_scroll_to_edge(true);
+    assert_eq!(sft.state.selected(), Some(0));
+  }
 }
@@ -0,0 +1,114 @@
+//! Verifying with the public half of a private key, for when only the private key is at hand
+
+use jsonwebtoken::{Algorithm, DecodingKey};
+use ring::{
+  rand::SystemRandom,
+  signature::{
+    EcdsaKeyPair, Ed25519KeyPair, KeyPair, RsaKeyPair, ECDSA_P256_SHA256_FIXED_SIGNING,
+    ECDSA_P384_SHA384_FIXED_SIGNING,
+  },
+};
+
+use super::{certificate::der_element, utils::pem_to_der};
+
+const SEQUENCE: u8 = 0x30;
+const INTEGER: u8 = 0x02;
+
+/// a PKCS#8 or PKCS#1 private key, PEM or DER. Both start with a version, which public keys and
+/// certificates don't
+pub fn is_private_key(content: &[u8]) -> bool {
+  let pem = pem_to_der(content);
+  let der = pem.as_deref().unwrap_or(content);
+  let Some((SEQUENCE, content, _)) = der_element(der) else {
+    return false;
+  };
+  matches!(der_element(content), Some((INTEGER, version, _)) if version.len() == 1)
+}
+
+/// the public key of the private key in `content` to verify `alg` with, `None` when it's no
+/// private key of the kind `alg` needs
+pub fn public_key_from_private(alg: &Algorithm, content: &[u8]) -> Option<DecodingKey> {
+  if !is_private_key(content) {
+    return None;
+  }
+  let pem = pem_to_der(content);
+  let der = pem.as_deref().unwrap_or(content);
+  match alg {
+    Algorithm::RS256
+    | Algorithm::RS384
+    | Algorithm::RS512
+    | Algorithm::PS256
+    | Algorithm::PS384
+    | Algorithm::PS512 => {
+      let key = RsaKeyPair::from_pkcs8(der)
+        .or_else(|_| RsaKeyPair::from_der(der))
+        .ok()?;
+      Some(DecodingKey::from_rsa_der(key.public().as_ref()))
+    }
+    Algorithm::ES256 | Algorithm::ES384 => {
+      let signing = if *alg == Algorithm::ES256 {
+        &ECDSA_P256_SHA256_FIXED_SIGNING
+      } else {
+        &ECDSA_P384_SHA384_FIXED_SIGNING
+      };
+      let key = EcdsaKeyPair::from_pkcs8(signing, der, &SystemRandom::new()).ok()?;
+      Some(DecodingKey::from_ec_der(key.public_key().as_ref()))
+    }
+    Algorithm::EdDSA => {
+      let key = Ed25519KeyPair::from_pkcs8_maybe_unchecked(der).ok()?;
+      Some(DecodingKey::from_ed_der(key.public_key().as_ref()))
+    }
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::app::utils::slurp_file;
+
+  fn key(file: &str) -> Vec<u8> {
+    slurp_file(format!("./test_data/{file}")).unwrap()
+  }
+
+  #[test]
+  fn test_is_private_key() {
+    for file in [
+      "test_rsa_private_key.pem",
+      "test_rsa_private_key.der",
+      "test_ecdsa_private_key.pk8",
+      "test_eddsa_private_key.pem",
+    ] {
+      assert!(is_private_key(&key(file)), "{file}");
+    }
+    for file in [
+      "test_rsa_public_key.pem",
+      "test_rsa_public_key.der",
+      "test_rsa_cert_bundle.crt",
+      "test_eddsa_public_key.pem",
+    ] {
+      assert!(!is_private_key(&key(file)), "{file}");
+    }
+  }
+
+  #[test]
+  fn test_public_key_from_private() {
+    for (alg, file) in [
+      (Algorithm::RS256, "test_rsa_private_key.pem"),
+      (Algorithm::PS512, "test_rsa_private_key.der"),
+      (Algorithm::ES256, "test_ecdsa_private_key.pk8"),
+      (Algorithm::ES384, "test_ecdsa_private_key.pem"),
+      (Algorithm::EdDSA, "test_eddsa_private_key.pem"),
+    ] {
+      assert!(
+        public_key_from_private(&alg, &key(file)).is_some(),
+        "{file}"
+      );
+    }
+    // the key must fit the alg
+    assert!(
+      public_key_from_private(&Algorithm::ES256, &key("test_ecdsa_private_key.pem")).is_none()
+    );
+    assert!(public_key_from_private(&Algorithm::RS256, &key("test_rsa_public_key.pem")).is_none());
+  }
+}
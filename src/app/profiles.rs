@@ -0,0 +1,293 @@
+//! Named profiles bundling the key, the expected issuer and audiences, the allowed algs and the
+//! leeway of an environment like `staging` or `prod`. They're read from [`PROFILES_FILE`] in the
+//! config dir, picked with `--profile` or switched in the TUI with the profile picker
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde_derive::Deserialize;
+
+use super::{
+  config::Config,
+  models::StatefulTable,
+  utils::{is_streamed, strip_leading_symbol, JWKS_PREFIX},
+  App,
+};
+
+/// file in the config dir the profiles are read from
+pub const PROFILES_FILE: &str = "profiles.json";
+
+/// profile used when none is picked with `--profile`, if there is one by that name
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The options of an environment, like
+/// `{"secret": "@staging.pem", "issuer": "https://staging", "audiences": ["api"], "leeway": 30}`.
+/// Options left out are taken from the command line
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+  /// key source in the formats of `--secret`
+  pub secret: Option<String>,
+  pub issuer: Option<String>,
+  pub audiences: Vec<String>,
+  /// weak algs not warned about, like `--allow-alg`
+  pub allow_algs: Vec<String>,
+  pub leeway: Option<i64>,
+}
+
+impl Profile {
+  /// applies the profile to `config` over the options of `base`, those given on the command line
+  /// take precedence. `overridden` are the arguments given, like for the settings
+  pub fn apply(&self, config: &mut Config, base: &Config, overridden: &[&str]) {
+    config.issuer = base.issuer.clone().or_else(|| self.issuer.clone());
+    config.audiences = if base.audiences.is_empty() {
+      self.audiences.clone()
+    } else {
+      base.audiences.clone()
+    };
+    config.leeway = match self.leeway {
+      Some(leeway) if !overridden.contains(&"leeway") => leeway,
+      _ => base.leeway,
+    };
+    config.weak_algs = base.weak_algs.clone();
+    for alg in &self.allow_algs {
+      config.weak_algs.remove(alg);
+    }
+  }
+
+  /// resolves a relative key file of the secret, `@path` or `jwks:@path`, against `dir`, the
+  /// directory of the profiles file, rather than the working directory
+  fn resolve_secret(&mut self, dir: &Path) {
+    let Some(secret) = self.secret.as_mut() else {
+      return;
+    };
+    let (prefix, file) = match secret.strip_prefix(JWKS_PREFIX) {
+      Some(jwks) => (JWKS_PREFIX, jwks),
+      None => ("", secret.as_str()),
+    };
+    if !file.starts_with('@') {
+      return;
+    }
+    let path = strip_leading_symbol(file);
+    if Path::new(&path).is_relative() && !is_streamed(&path) {
+      *secret = format!("{prefix}@{}", dir.join(path).display());
+    }
+  }
+}
+
+/// reads the profiles by name, a missing file has none. Key files of the secrets are resolved
+/// relative to the file
+pub fn load_profiles(path: &Path) -> Result<BTreeMap<String, Profile>, String> {
+  let Ok(content) = fs::read(path) else {
+    return Ok(BTreeMap::new());
+  };
+  let mut profiles: BTreeMap<String, Profile> = serde_json::from_slice(&content)
+    .map_err(|e| format!("Profiles: unable to read {}: {e}", path.display()))?;
+  if let Some(dir) = path.parent() {
+    profiles
+      .values_mut()
+      .for_each(|profile| profile.resolve_secret(dir));
+  }
+  Ok(profiles)
+}
+
+/// the profile named `name`, or the default one when `None`. Only an unknown name is an error
+pub fn select_profile<'a>(
+  profiles: &'a BTreeMap<String, Profile>,
+  name: Option<&str>,
+) -> Result<Option<(&'a String, &'a Profile)>, String> {
+  match name {
+    None => Ok(profiles.get_key_value(DEFAULT_PROFILE)),
+    Some(name) => match profiles.get_key_value(name) {
+      Some(profile) => Ok(Some(profile)),
+      None if profiles.is_empty() => Err(format!(
+        "Profiles: there is no profile `{name}`, none are defined"
+      )),
+      None => Err(format!(
+        "Profiles: there is no profile `{name}`, only {}",
+        profiles
+          .keys()
+          .map(|name| format!("`{name}`"))
+          .collect::<Vec<_>>()
+          .join(", ")
+      )),
+    },
+  }
+}
+
+/// State of the profiles in the TUI
+#[derive(Debug, Clone, Default)]
+pub struct Profiles {
+  pub profiles: BTreeMap<String, Profile>,
+  /// name of the profile in use
+  pub active: Option<String>,
+  /// the options profiles are applied over, as given on the command line and in the settings
+  pub base: Config,
+  /// arguments given on the command line, which profiles don't change, `secret` for the secret
+  pub overridden: Vec<&'static str>,
+  /// popup to pick a profile
+  pub menu: Option<StatefulTable<String>>,
+}
+
+/// switches to the profile named `name`, replacing the options of the last one. The secret is
+/// only replaced when the profile has one
+pub fn use_profile(app: &mut App, name: &str) {
+  let Some(profile) = app.profiles.profiles.get(name).cloned() else {
+    return;
+  };
+  let profiles = &app.profiles;
+  profile.apply(&mut app.config, &profiles.base, &profiles.overridden);
+  let secret = profile
+    .secret
+    .as_ref()
+    .filter(|_| !profiles.overridden.contains(&"secret"));
+  let mut message = format!("Using the profile {name}");
+  if let Some(secret) = secret {
+    app.data.decoder.secret.input = secret.clone().into();
+    if secret.starts_with('@') {
+      // key files are named, never inline secrets
+      message.push_str(&format!(
+        ", verifying with {}",
+        strip_leading_symbol(secret)
+      ));
+    }
+  }
+  app.profiles.active = Some(name.into());
+  app.notify(&message);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn profiles() -> BTreeMap<String, Profile> {
+    serde_json::from_str(
+      r#"{
+        "default": { "leeway": 30 },
+        "staging": {
+          "secret": "@./test_data/test_rsa_public_key.pem",
+          "issuer": "https://staging",
+          "audiences": ["api"],
+          "allow_algs": ["HS1"],
+          "leeway": 120
+        }
+      }"#,
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn test_profile_apply() {
+    let profiles = profiles();
+    let base = Config::default();
+    let mut config = base.clone();
+    profiles["staging"].apply(&mut config, &base, &[]);
+    assert_eq!(config.issuer.as_deref(), Some("https://staging"));
+    assert_eq!(config.audiences, ["api"]);
+    assert_eq!(config.leeway, 120);
+    assert!(!config.weak_algs.contains_key("HS1"));
+
+    // the options of the last profile are replaced
+    profiles["default"].apply(&mut config, &base, &[]);
+    assert_eq!(config.issuer, None);
+    assert!(config.audiences.is_empty());
+    assert_eq!(config.leeway, 30);
+    assert!(config.weak_algs.contains_key("HS1"));
+
+    // the command line wins
+    let base = Config {
+      issuer: Some("https://prod".into()),
+      leeway: 5,
+      ..Config::default()
+    };
+    profiles["staging"].apply(&mut config, &base, &["leeway"]);
+    assert_eq!(config.issuer.as_deref(), Some("https://prod"));
+    assert_eq!(config.leeway, 5);
+
+    assert!(serde_json::from_str::<Profile>(r#"{"audience": "api"}"#).is_err());
+  }
+
+  #[test]
+  fn test_load_profiles() {
+    let dir = std::env::temp_dir().join("jwt-ui-test-profiles");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(PROFILES_FILE);
+    fs::write(
+      &path,
+      r#"{
+        "file": { "secret": "@staging.pem" },
+        "jwks": { "secret": "jwks:@keys/jwks.json" },
+        "absolute": { "secret": "@/etc/prod.pem" },
+        "stdin": { "secret": "@-" },
+        "text": { "secret": "secret" }
+      }"#,
+    )
+    .unwrap();
+    let profiles = load_profiles(&path).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let secret = |name: &str| profiles[name].secret.clone().unwrap();
+    assert_eq!(
+      secret("file"),
+      format!("@{}", dir.join("staging.pem").display())
+    );
+    assert_eq!(
+      secret("jwks"),
+      format!("jwks:@{}", dir.join("keys/jwks.json").display())
+    );
+    assert_eq!(secret("absolute"), "@/etc/prod.pem");
+    assert_eq!(secret("stdin"), "@-");
+    assert_eq!(secret("text"), "secret");
+
+    assert_eq!(load_profiles(&dir.join(PROFILES_FILE)), Ok(BTreeMap::new()));
+  }
+
+  #[test]
+  fn test_select_profile() {
+    let profiles = profiles();
+    assert_eq!(
+      select_profile(&profiles, None).unwrap().unwrap().0,
+      DEFAULT_PROFILE
+    );
+    assert_eq!(
+      select_profile(&profiles, Some("staging"))
+        .unwrap()
+        .unwrap()
+        .0,
+      "staging"
+    );
+    assert_eq!(
+      select_profile(&profiles, Some("prod")),
+      Err("Profiles: there is no profile `prod`, only `default`, `staging`".into())
+    );
+    assert_eq!(select_profile(&BTreeMap::new(), None), Ok(None));
+    assert_eq!(
+      select_profile(&BTreeMap::new(), Some("prod")),
+      Err("Profiles: there is no profile `prod`, none are defined".into())
+    );
+  }
+
+  #[test]
+  fn test_use_profile() {
+    let mut app = App::new(250, None, "".into());
+    app.profiles.profiles = profiles();
+    use_profile(&mut app, "staging");
+    assert_eq!(app.profiles.active.as_deref(), Some("staging"));
+    assert_eq!(app.config.leeway, 120);
+    assert_eq!(
+      app.data.decoder.secret.input.value(),
+      "@./test_data/test_rsa_public_key.pem"
+    );
+    assert_eq!(
+      app.notification.as_ref().unwrap().message,
+      "Using the profile staging, verifying with ./test_data/test_rsa_public_key.pem"
+    );
+
+    // the secret stays when the profile has none
+    use_profile(&mut app, "default");
+    assert_eq!(app.config.leeway, 30);
+    assert_eq!(
+      app.data.decoder.secret.input.value(),
+      "@./test_data/test_rsa_public_key.pem"
+    );
+  }
+}
@@ -0,0 +1,269 @@
+//! Selective Disclosure JWTs (SD-JWT), an issuer JWT with disclosures appended: `<issuer JWT>~<disclosure>~…~<KB-JWT>`
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use super::utils::DecodeError;
+
+/// key of the digests of disclosed object properties
+const SD: &str = "_sd";
+/// key of the digest of a disclosed array element, `{"...": "<digest>"}`
+const ARRAY_ELEMENT: &str = "...";
+
+/// The parts following the issuer JWT of an SD-JWT
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SdJwt {
+  pub disclosures: Vec<Disclosure>,
+  /// the key binding JWT presented with the token, it isn't verified
+  pub key_binding: Option<String>,
+}
+
+/// A disclosure, a base64url encoded JSON array `[salt, name, value]` for an object property or
+/// `[salt, value]` for an array element
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disclosure {
+  pub encoded: String,
+  pub salt: String,
+  /// `None` for array elements
+  pub name: Option<String>,
+  pub value: Value,
+  /// the payload holds the digest of the disclosure, so that its claim was reconstructed
+  pub referenced: bool,
+}
+
+impl Disclosure {
+  /// reads the disclosure at `index`, counted from 1
+  pub fn parse(encoded: &str, index: usize) -> Result<Self, DecodeError> {
+    let invalid = |e: &str| DecodeError::InvalidDisclosure(index, e.into());
+    let json = URL_SAFE_NO_PAD
+      .decode(encoded.trim_end_matches('='))
+      .map_err(|_| invalid("it's not base64url"))?;
+    let array = match serde_json::from_slice(&json) {
+      Ok(Value::Array(array)) => array,
+      _ => return Err(invalid("it's not a JSON array")),
+    };
+    let (salt, name, value) = match array.as_slice() {
+      [Value::String(salt), Value::String(name), value] => (salt, Some(name.clone()), value),
+      [Value::String(salt), value] => (salt, None, value),
+      _ => {
+        return Err(invalid(
+          "it's neither [salt, name, value] nor [salt, value]",
+        ))
+      }
+    };
+    Ok(Disclosure {
+      encoded: encoded.into(),
+      salt: salt.clone(),
+      name,
+      value: value.clone(),
+      referenced: false,
+    })
+  }
+
+  /// base64url encoded digest the payload references the disclosure by, `None` for an
+  /// unsupported `_sd_alg`
+  pub fn digest(&self, alg: &str) -> Option<String> {
+    let digest = match alg {
+      "sha-256" => Sha256::digest(&self.encoded).to_vec(),
+      "sha-384" => Sha384::digest(&self.encoded).to_vec(),
+      "sha-512" => Sha512::digest(&self.encoded).to_vec(),
+      _ => return None,
+    };
+    Some(URL_SAFE_NO_PAD.encode(digest))
+  }
+}
+
+/// the encoded disclosures of an SD-JWT and its key binding JWT
+pub type SdJwtParts<'a> = (Vec<&'a str>, Option<&'a str>);
+
+/// splits an SD-JWT into the issuer JWT and the rest, `None` for a plain JWT
+pub fn split_sd_jwt(token: &str) -> (&str, Option<SdJwtParts<'_>>) {
+  let mut parts = token.split('~').collect::<Vec<_>>();
+  if parts.len() < 2 {
+    return (token, None);
+  }
+  let jwt = parts.remove(0);
+  // a presentation without key binding ends with `~`
+  let key_binding = parts.pop().filter(|kb| !kb.is_empty());
+  (jwt, Some((parts, key_binding)))
+}
+
+/// the issuer JWT of an SD-JWT, plain tokens are returned as-is
+pub fn issuer_jwt(token: &str) -> &str {
+  split_sd_jwt(token).0
+}
+
+/// reads the disclosures of an SD-JWT, reconstructing the claims they disclose in the payload.
+/// Digests of disclosed claims are taken out of `_sd`, the undisclosed ones are kept
+pub fn disclose(
+  payload: &mut Map<String, Value>,
+  disclosures: &[&str],
+  key_binding: Option<&str>,
+) -> Result<SdJwt, DecodeError> {
+  let mut disclosures = disclosures
+    .iter()
+    .enumerate()
+    .map(|(i, encoded)| Disclosure::parse(encoded, i + 1))
+    .collect::<Result<Vec<_>, _>>()?;
+  let alg = payload
+    .get("_sd_alg")
+    .and_then(Value::as_str)
+    .unwrap_or("sha-256");
+  let digests = disclosures
+    .iter()
+    .map(|d| d.digest(alg))
+    .collect::<Option<Vec<_>>>();
+  if let Some(digests) = digests {
+    reveal_object(payload, &mut disclosures, &digests);
+  }
+  Ok(SdJwt {
+    disclosures,
+    key_binding: key_binding.map(String::from),
+  })
+}
+
+/// index of the disclosure that has `digest` and hasn't been used yet
+fn find(
+  digest: &Value,
+  array_element: bool,
+  disclosures: &[Disclosure],
+  digests: &[String],
+) -> Option<usize> {
+  let digest = digest.as_str()?;
+  disclosures
+    .iter()
+    .zip(digests)
+    .position(|(d, known)| known == digest && !d.referenced && d.name.is_none() == array_element)
+}
+
+fn reveal_object(map: &mut Map<String, Value>, disclosures: &mut [Disclosure], digests: &[String]) {
+  for value in map.values_mut() {
+    reveal(value, disclosures, digests);
+  }
+  let Some(Value::Array(sd)) = map.get_mut(SD) else {
+    return;
+  };
+  let mut revealed = vec![];
+  sd.retain(|digest| match find(digest, false, disclosures, digests) {
+    Some(index) => {
+      disclosures[index].referenced = true;
+      revealed.push(index);
+      false
+    }
+    None => true,
+  });
+  if sd.is_empty() {
+    map.shift_remove(SD);
+  }
+  for index in revealed {
+    let mut value = disclosures[index].value.clone();
+    reveal(&mut value, disclosures, digests);
+    map.insert(disclosures[index].name.clone().unwrap_or_default(), value);
+  }
+}
+
+fn reveal(value: &mut Value, disclosures: &mut [Disclosure], digests: &[String]) {
+  match value {
+    Value::Object(map) => reveal_object(map, disclosures, digests),
+    Value::Array(items) => {
+      for item in items {
+        let element = match item {
+          Value::Object(map) if map.len() == 1 => map.get(ARRAY_ELEMENT),
+          _ => None,
+        };
+        if let Some(index) = element.and_then(|digest| find(digest, true, disclosures, digests)) {
+          disclosures[index].referenced = true;
+          *item = disclosures[index].value.clone();
+        }
+        reveal(item, disclosures, digests);
+      }
+    }
+    _ => {}
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn encode(disclosure: Value) -> String {
+    URL_SAFE_NO_PAD.encode(disclosure.to_string())
+  }
+
+  #[test]
+  fn test_split_sd_jwt() {
+    assert_eq!(split_sd_jwt("a.b.c"), ("a.b.c", None));
+    assert_eq!(split_sd_jwt("a.b.c~"), ("a.b.c", Some((vec![], None))));
+    assert_eq!(
+      split_sd_jwt("a.b.c~d1~d2~"),
+      ("a.b.c", Some((vec!["d1", "d2"], None)))
+    );
+    assert_eq!(
+      split_sd_jwt("a.b.c~d1~x.y.z"),
+      ("a.b.c", Some((vec!["d1"], Some("x.y.z"))))
+    );
+    assert_eq!(issuer_jwt("a.b.c~d1~"), "a.b.c");
+  }
+
+  #[test]
+  fn test_disclose() {
+    let name = encode(json!(["salt1", "given_name", "John"]));
+    let street = encode(json!(["salt2", "street", "Main St"]));
+    let address = Disclosure::parse(&street, 1).unwrap();
+    let address_digest = address.digest("sha-256").unwrap();
+    let region = encode(json!(["salt3", "address", {"_sd": [address_digest]}]));
+    let nationality = encode(json!(["salt4", "DE"]));
+    let unused = encode(json!(["salt5", "email", "john@example.com"]));
+    let digest = |encoded: &str| Disclosure::parse(encoded, 1).unwrap().digest("sha-256");
+
+    let mut payload = json!({
+      "iss": "issuer",
+      "_sd": [digest(&name), digest(&region), "undisclosed"],
+      "nationalities": [{"...": digest(&nationality)}, "FR"],
+      "_sd_alg": "sha-256",
+    });
+    let sd_jwt = disclose(
+      payload.as_object_mut().unwrap(),
+      &[&name, &region, &street, &nationality, &unused],
+      Some("kb"),
+    )
+    .unwrap();
+
+    assert_eq!(
+      payload,
+      json!({
+        "iss": "issuer",
+        "_sd": ["undisclosed"],
+        "nationalities": ["DE", "FR"],
+        "_sd_alg": "sha-256",
+        "given_name": "John",
+        "address": {"street": "Main St"},
+      })
+    );
+    let referenced = sd_jwt
+      .disclosures
+      .iter()
+      .map(|d| d.referenced)
+      .collect::<Vec<_>>();
+    assert_eq!(referenced, [true, true, true, true, false]);
+    assert_eq!(sd_jwt.disclosures[3].name, None);
+    assert_eq!(sd_jwt.key_binding.as_deref(), Some("kb"));
+
+    // the claims aren't reconstructed for an unknown digest algorithm
+    let mut payload = json!({"_sd": [digest(&name)], "_sd_alg": "md5"});
+    let sd_jwt = disclose(payload.as_object_mut().unwrap(), &[&name], None).unwrap();
+    assert_eq!(payload["_sd"], json!([digest(&name)]));
+    assert!(!sd_jwt.disclosures[0].referenced);
+
+    assert_eq!(
+      disclose(&mut Map::new(), &["e30"], None),
+      Err(DecodeError::InvalidDisclosure(
+        1,
+        "it's not a JSON array".into()
+      ))
+    );
+  }
+}
@@ -0,0 +1,443 @@
+//! Settings tab to change options at runtime. Changes are saved to [`SETTINGS_FILE`] in the
+//! config dir and applied again on the next start, unless given on the command line
+
+use std::{
+  collections::BTreeMap,
+  fmt, fs, io,
+  path::{Path, PathBuf},
+};
+
+use super::{config::DateFormat, models::StatefulTable, App, InputMode, TextInput};
+
+/// file in the config dir the settings are saved to
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// reason given for algs added to the weak ones in the settings
+const WEAK_ALG_REASON: &str = "listed as weak in the settings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+  LightTheme,
+  HighContrast,
+  UtcDates,
+  DateFormat,
+  ClaimLabels,
+  ScrollStep,
+  PageStep,
+  Leeway,
+  WeakAlgs,
+  Strict,
+  VimKeys,
+  RequireSignature,
+  AllowExpiredCert,
+  TickRate,
+}
+
+impl Setting {
+  /// in the order shown
+  pub const ALL: [Setting; 14] = [
+    Setting::LightTheme,
+    Setting::HighContrast,
+    Setting::UtcDates,
+    Setting::DateFormat,
+    Setting::ClaimLabels,
+    Setting::ScrollStep,
+    Setting::PageStep,
+    Setting::Leeway,
+    Setting::WeakAlgs,
+    Setting::Strict,
+    Setting::VimKeys,
+    Setting::RequireSignature,
+    Setting::AllowExpiredCert,
+    Setting::TickRate,
+  ];
+
+  /// key in the settings file
+  pub fn key(&self) -> &'static str {
+    match self {
+      Setting::LightTheme => "light_theme",
+      Setting::HighContrast => "high_contrast",
+      Setting::UtcDates => "utc_dates",
+      Setting::DateFormat => "date_format",
+      Setting::ClaimLabels => "claim_labels",
+      Setting::ScrollStep => "scroll_step",
+      Setting::PageStep => "page_step",
+      Setting::Leeway => "leeway",
+      Setting::WeakAlgs => "weak_algs",
+      Setting::Strict => "strict",
+      Setting::VimKeys => "vim_keys",
+      Setting::RequireSignature => "require_signature",
+      Setting::AllowExpiredCert => "allow_expired_cert",
+      Setting::TickRate => "tick_rate",
+    }
+  }
+
+  /// command line arguments taking precedence over the saved value
+  pub fn args(&self) -> &'static [&'static str] {
+    match self {
+      Setting::LightTheme | Setting::UtcDates => &[],
+      Setting::HighContrast => &["high_contrast"],
+      Setting::DateFormat => &["date_format"],
+      Setting::ClaimLabels => &["claim_label"],
+      Setting::ScrollStep => &["scroll_step"],
+      Setting::PageStep => &["page_step"],
+      Setting::Leeway => &["leeway"],
+      Setting::WeakAlgs => &["weak_alg", "allow_alg"],
+      Setting::Strict => &["strict"],
+      Setting::VimKeys => &["vim_keys"],
+      Setting::RequireSignature => &["require_signature"],
+      Setting::AllowExpiredCert => &["allow_expired_cert"],
+      Setting::TickRate => &["tick_rate"],
+    }
+  }
+
+  /// switched on and off with `<enter>` instead of edited
+  pub fn is_toggle(&self) -> bool {
+    matches!(
+      self,
+      Setting::LightTheme
+        | Setting::HighContrast
+        | Setting::UtcDates
+        | Setting::Strict
+        | Setting::VimKeys
+        | Setting::RequireSignature
+        | Setting::AllowExpiredCert
+    )
+  }
+
+  /// the events are produced at the tick rate the UI was started with
+  pub fn needs_restart(&self) -> bool {
+    *self == Setting::TickRate
+  }
+
+  pub fn value(&self, app: &App) -> String {
+    let toggle = |on: bool| if on { "on" } else { "off" }.to_string();
+    match self {
+      Setting::LightTheme => toggle(app.light_theme),
+      Setting::HighContrast => toggle(app.high_contrast),
+      Setting::UtcDates => toggle(app.data.decoder.utc_dates),
+      Setting::DateFormat => app.config.date_format.name().into(),
+      Setting::ClaimLabels => app
+        .config
+        .claim_labels
+        .iter()
+        .map(|(claim, label)| format!("{claim}={label}"))
+        .collect::<Vec<_>>()
+        .join(", "),
+      Setting::ScrollStep => app.config.scroll_step.to_string(),
+      Setting::PageStep => app.config.page_step.to_string(),
+      Setting::Leeway => app.config.leeway.to_string(),
+      Setting::WeakAlgs => app
+        .config
+        .weak_algs
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", "),
+      Setting::Strict => toggle(app.config.strict),
+      Setting::VimKeys => toggle(app.config.vim_keys),
+      Setting::RequireSignature => toggle(app.config.require_signature),
+      Setting::AllowExpiredCert => toggle(app.config.allow_expired_cert),
+      Setting::TickRate => app.tick_rate.to_string(),
+    }
+  }
+
+  /// parses `value` like it's shown and applies it
+  pub fn set(&self, app: &mut App, value: &str) -> Result<(), String> {
+    let value = value.trim();
+    let toggle = || match value {
+      "on" | "true" => Ok(true),
+      "off" | "false" => Ok(false),
+      _ => Err(format!("{self}: `{value}` is neither `on` nor `off`")),
+    };
+    let step = || match value.parse::<usize>() {
+      Ok(step) if step > 0 => Ok(step),
+      _ => Err(format!("{self}: `{value}` is not a number of at least 1")),
+    };
+    match self {
+      Setting::LightTheme => app.light_theme = toggle()?,
+      Setting::HighContrast => app.high_contrast = toggle()?,
+      Setting::UtcDates => app.data.decoder.utc_dates = toggle()?,
+      Setting::DateFormat => {
+        app.config.date_format = value
+          .parse::<DateFormat>()
+          .map_err(|e| format!("{self}: {e}"))?
+      }
+      Setting::ClaimLabels => {
+        let mut claim_labels = BTreeMap::new();
+        for pair in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+          match pair.split_once('=').map(|(c, l)| (c.trim(), l.trim())) {
+            Some((claim, label)) if !claim.is_empty() && !label.is_empty() => {
+              claim_labels.insert(claim.to_string(), label.to_string());
+            }
+            _ => return Err(format!("{self}: `{pair}` is not `CLAIM=LABEL`")),
+          }
+        }
+        app.config.claim_labels = claim_labels;
+      }
+      Setting::ScrollStep => app.config.scroll_step = step()?,
+      Setting::PageStep => app.config.page_step = step()?,
+      Setting::Leeway => {
+        app.config.leeway = match value.parse::<i64>() {
+          Ok(leeway) if leeway >= 0 => leeway,
+          _ => return Err(format!("{self}: `{value}` is not a number of seconds")),
+        }
+      }
+      Setting::WeakAlgs => {
+        let mut weak_algs = BTreeMap::new();
+        for alg in value.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+          // known algs keep the reason they are weak for
+          let reason = app
+            .config
+            .weak_algs
+            .get(alg)
+            .cloned()
+            .unwrap_or_else(|| WEAK_ALG_REASON.into());
+          weak_algs.insert(alg.to_string(), reason);
+        }
+        app.config.weak_algs = weak_algs;
+      }
+      Setting::Strict => app.config.strict = toggle()?,
+      Setting::VimKeys => app.config.vim_keys = toggle()?,
+      Setting::RequireSignature => app.config.require_signature = toggle()?,
+      Setting::AllowExpiredCert => app.config.allow_expired_cert = toggle()?,
+      Setting::TickRate => {
+        app.tick_rate = match value.parse::<u64>() {
+          Ok(rate) if (1..1000).contains(&rate) => rate,
+          _ => return Err(format!("{self}: `{value}` is not between 1 and 999 ms")),
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for Setting {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      Setting::LightTheme => "Light theme",
+      Setting::HighContrast => "High contrast",
+      Setting::UtcDates => "Dates in UTC",
+      Setting::DateFormat => "Date format",
+      Setting::ClaimLabels => "Claim labels",
+      Setting::ScrollStep => "Scroll step (lines)",
+      Setting::PageStep => "Page step (lines)",
+      Setting::Leeway => "Leeway (seconds)",
+      Setting::WeakAlgs => "Weak algs",
+      Setting::Strict => "Strict base64",
+      Setting::VimKeys => "Vim keys",
+      Setting::RequireSignature => "Require signature",
+      Setting::AllowExpiredCert => "Allow expired certificates",
+      Setting::TickRate => "Tick rate (ms)",
+    };
+    write!(f, "{name}")
+  }
+}
+
+/// State of the settings tab
+#[derive(Debug, Clone)]
+pub struct SettingsView {
+  pub table: StatefulTable<Setting>,
+  /// value of the selected setting being edited
+  pub input: TextInput,
+  /// where changes are saved, not saved when `None`
+  pub path: Option<PathBuf>,
+}
+
+impl Default for SettingsView {
+  fn default() -> Self {
+    Self {
+      table: StatefulTable::with_items(Setting::ALL.to_vec()),
+      input: TextInput::default(),
+      path: None,
+    }
+  }
+}
+
+/// toggles the selected setting, or starts editing its value
+pub fn select_setting(app: &mut App) {
+  let Some(setting) = app.settings.table.get_selected_item_copy() else {
+    return;
+  };
+  if setting.is_toggle() {
+    let on = setting.value(app) == "on";
+    let value = if on { "off" } else { "on" };
+    if let Err(e) = setting.set(app, value) {
+      app.notify(&e);
+      return;
+    }
+    changed(app, setting);
+  } else {
+    app.settings.input = TextInput::new(setting.value(app));
+    app.settings.input.input_mode = InputMode::Editing;
+  }
+}
+
+/// applies the edited value of the selected setting, editing goes on when it's invalid
+pub fn apply_setting_edit(app: &mut App) {
+  let Some(setting) = app.settings.table.get_selected_item_copy() else {
+    return;
+  };
+  let value = app.settings.input.input.value().to_string();
+  match setting.set(app, &value) {
+    Ok(()) => {
+      app.settings.input.input_mode = InputMode::Normal;
+      changed(app, setting);
+    }
+    Err(e) => app.notify(&e),
+  }
+}
+
+/// saves the settings and tells whether the change is already in effect
+fn changed(app: &mut App, setting: Setting) {
+  let mut message = format!("{setting} set to {}", setting.value(app));
+  if setting.needs_restart() {
+    message.push_str(", takes effect after a restart");
+  }
+  if let Some(path) = app.settings.path.clone() {
+    if let Err(e) = save_settings(app, &path) {
+      message = format!("{message}, but unable to save it: {e}");
+    }
+  }
+  app.notify(&message);
+}
+
+/// saves all the settings as strings like they're shown, e.g. `"leeway": "60"`
+pub fn save_settings(app: &App, path: &Path) -> io::Result<()> {
+  let settings = Setting::ALL
+    .iter()
+    .map(|setting| (setting.key(), setting.value(app)))
+    .collect::<BTreeMap<_, _>>();
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  fs::write(path, serde_json::to_string_pretty(&settings)?)
+}
+
+/// applies the saved settings, except those whose arguments are in `overridden`. Returns warnings
+/// for values that can't be applied, a missing file is no reason to warn
+pub fn load_settings(app: &mut App, path: &Path, overridden: &[&str]) -> Vec<String> {
+  let Ok(content) = fs::read(path) else {
+    return vec![];
+  };
+  let saved: BTreeMap<String, String> = match serde_json::from_slice(&content) {
+    Ok(saved) => saved,
+    Err(e) => return vec![format!("Settings: unable to read {}: {e}", path.display())],
+  };
+  let mut warnings = vec![];
+  for setting in Setting::ALL {
+    let Some(value) = saved.get(setting.key()) else {
+      continue;
+    };
+    if setting.args().iter().any(|arg| overridden.contains(arg)) {
+      continue;
+    }
+    if let Err(e) = setting.set(app, value) {
+      warnings.push(format!("Settings: {e}"));
+    }
+  }
+  warnings
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_setting_set() {
+    let mut app = App::new(250, None, "".into());
+    Setting::Leeway.set(&mut app, " 30 ").unwrap();
+    assert_eq!(app.config.leeway, 30);
+    Setting::UtcDates.set(&mut app, "on").unwrap();
+    assert!(app.data.decoder.utc_dates);
+    Setting::DateFormat.set(&mut app, "rfc2822").unwrap();
+    assert_eq!(app.config.date_format, DateFormat::Rfc2822);
+    assert_eq!(Setting::DateFormat.value(&app), "rfc2822");
+
+    Setting::WeakAlgs.set(&mut app, "none, HS256,").unwrap();
+    assert_eq!(Setting::WeakAlgs.value(&app), "HS256, none");
+    assert_eq!(app.config.weak_algs["none"], "the token is not signed");
+    assert_eq!(app.config.weak_algs["HS256"], WEAK_ALG_REASON);
+
+    Setting::ClaimLabels
+      .set(&mut app, "x-tn = Tenant ID, r_=Roles")
+      .unwrap();
+    assert_eq!(app.config.claim_labels["x-tn"], "Tenant ID");
+    assert_eq!(Setting::ClaimLabels.value(&app), "r_=Roles, x-tn=Tenant ID");
+    assert_eq!(
+      Setting::ClaimLabels.set(&mut app, "x-tn"),
+      Err("Claim labels: `x-tn` is not `CLAIM=LABEL`".into())
+    );
+    assert_eq!(app.config.claim_labels.len(), 2);
+
+    assert_eq!(
+      Setting::PageStep.set(&mut app, "0"),
+      Err("Page step (lines): `0` is not a number of at least 1".into())
+    );
+    assert_eq!(
+      Setting::Strict.set(&mut app, "yes"),
+      Err("Strict base64: `yes` is neither `on` nor `off`".into())
+    );
+    assert_eq!(
+      Setting::DateFormat.set(&mut app, "unix"),
+      Err("Date format: `unix` is neither `iso8601`, `human` nor `rfc2822`".into())
+    );
+    assert!(Setting::TickRate.set(&mut app, "1000").is_err());
+    assert_eq!(app.config.page_step, 10);
+    assert!(!app.config.strict);
+  }
+
+  #[test]
+  fn test_select_setting() {
+    let mut app = App::new(250, None, "".into());
+    app.settings.table.state.select(Some(0));
+    select_setting(&mut app);
+    assert!(app.light_theme);
+    assert_eq!(
+      app.notification.as_ref().unwrap().message,
+      "Light theme set to on"
+    );
+
+    // values are edited and applied on enter
+    app.settings.table.state.select(Some(13));
+    select_setting(&mut app);
+    assert_eq!(app.settings.input.input_mode, InputMode::Editing);
+    assert_eq!(app.settings.input.input.value(), "250");
+    app.settings.input = TextInput::new("100".into());
+    app.settings.input.input_mode = InputMode::Editing;
+    apply_setting_edit(&mut app);
+    assert_eq!(app.tick_rate, 100);
+    assert_eq!(app.settings.input.input_mode, InputMode::Normal);
+    assert_eq!(
+      app.notification.as_ref().unwrap().message,
+      "Tick rate (ms) set to 100, takes effect after a restart"
+    );
+  }
+
+  #[test]
+  fn test_settings_round_trip() {
+    let path = std::env::temp_dir().join("jwt-ui-test-settings.json");
+    let mut app = App::new(250, None, "".into());
+    app.light_theme = true;
+    app.high_contrast = true;
+    app.config.leeway = 5;
+    app.config.vim_keys = true;
+    save_settings(&app, &path).unwrap();
+
+    let mut loaded = App::new(250, None, "".into());
+    assert!(load_settings(&mut loaded, &path, &["vim_keys"]).is_empty());
+    assert!(loaded.light_theme);
+    assert!(loaded.high_contrast);
+    assert_eq!(loaded.config.leeway, 5);
+    // given on the command line
+    assert!(!loaded.config.vim_keys);
+
+    std::fs::write(&path, r#"{"leeway": "soon", "unknown": "1"}"#).unwrap();
+    assert_eq!(
+      load_settings(&mut loaded, &path, &[]),
+      vec!["Settings: Leeway (seconds): `soon` is not a number of seconds".to_string()]
+    );
+    std::fs::remove_file(&path).unwrap();
+    assert!(load_settings(&mut loaded, &path, &[]).is_empty());
+  }
+}
@@ -0,0 +1,142 @@
+use std::{fs, io, path::Path};
+
+use serde_derive::{Deserialize, Serialize};
+
+use super::{ActiveBlock, App, Route, RouteId};
+
+/// Navigation state persisted between sessions. Never holds token or secret content
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NavigationState {
+  pub tab_index: usize,
+  pub active_block: Option<ActiveBlock>,
+  /// missing from state saved by older versions
+  #[serde(default)]
+  pub light_theme: bool,
+  /// references to key files only, see [`App::remember_secret`]
+  #[serde(default)]
+  pub recent_secrets: Vec<String>,
+}
+
+impl NavigationState {
+  pub fn from_app(app: &App) -> Self {
+    let active_block = match app.main_tabs.get_active_route().id {
+      RouteId::Decoder => Some(*app.data.decoder.blocks.get_active_block()),
+      RouteId::Encoder => Some(*app.data.encoder.blocks.get_active_block()),
+      RouteId::Help | RouteId::Settings => None,
+    };
+    NavigationState {
+      tab_index: app.main_tabs.index,
+      active_block,
+      light_theme: app.light_theme,
+      recent_secrets: app.recent_secrets.clone(),
+    }
+  }
+
+  /// restores the theme, the recent key files, the tab and the focused block within it
+  pub fn apply(&self, app: &mut App) {
+    app.light_theme = self.light_theme;
+    app.recent_secrets = self.recent_secrets.clone();
+    if self.tab_index >= app.main_tabs.items.len() {
+      return;
+    }
+    let mut route = app.main_tabs.set_index(self.tab_index).route;
+    if let Some(active_block) = self.active_block {
+      route = Route {
+        id: route.id,
+        active_block,
+      };
+    }
+    // blocks not belonging to the tab fall back to its first block
+    let route = match route.id {
+      RouteId::Decoder => *app.data.decoder.blocks.set_item(route),
+      RouteId::Encoder => *app.data.encoder.blocks.set_item(route),
+      RouteId::Help | RouteId::Settings => route,
+    };
+    app.push_navigation_route(route);
+  }
+
+  pub fn load(path: &Path) -> Option<Self> {
+    let content = fs::read(path).ok()?;
+    serde_json::from_slice(&content).ok()
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(self)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_navigation_state_round_trip() {
+    let mut app = App::new(250, None, "".into());
+    app.route_encoder();
+    app.data.encoder.blocks.next();
+    app.push_navigation_route(*app.data.encoder.blocks.get_active_item());
+    app.light_theme = true;
+    app.remember_secret("@./test_data/test_rsa_public_key.pem");
+
+    let state = NavigationState::from_app(&app);
+    assert_eq!(
+      state,
+      NavigationState {
+        tab_index: 1,
+        active_block: Some(ActiveBlock::EncoderPayload),
+        light_theme: true,
+        recent_secrets: vec!["@./test_data/test_rsa_public_key.pem".into()],
+      }
+    );
+
+    let path = std::env::temp_dir().join("jwt-ui-test-state.json");
+    state.save(&path).unwrap();
+    let loaded = NavigationState::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, state);
+
+    let mut app = App::new(250, None, "".into());
+    loaded.apply(&mut app);
+    assert_eq!(app.main_tabs.index, 1);
+    assert!(app.light_theme);
+    assert_eq!(app.recent_secrets, state.recent_secrets);
+    assert_eq!(
+      app.get_current_route().active_block,
+      ActiveBlock::EncoderPayload
+    );
+  }
+
+  #[test]
+  fn test_navigation_state_without_theme() {
+    let state: NavigationState =
+      serde_json::from_str(r#"{"tab_index":1,"active_block":null}"#).unwrap();
+    assert!(!state.light_theme);
+  }
+
+  #[test]
+  fn test_navigation_state_apply_with_invalid_block() {
+    let mut app = App::new(250, None, "".into());
+    NavigationState {
+      tab_index: 0,
+      active_block: Some(ActiveBlock::EncoderPayload),
+      ..NavigationState::default()
+    }
+    .apply(&mut app);
+
+    assert_eq!(app.get_current_route().id, RouteId::Decoder);
+    assert_eq!(
+      app.get_current_route().active_block,
+      ActiveBlock::DecoderToken
+    );
+
+    NavigationState {
+      tab_index: 5,
+      ..NavigationState::default()
+    }
+    .apply(&mut app);
+    assert_eq!(app.main_tabs.index, 0);
+  }
+}
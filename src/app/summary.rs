@@ -0,0 +1,723 @@
+use std::{collections::BTreeSet, fmt};
+
+use jsonwebtoken::errors::ErrorKind;
+use serde_json::Value;
+
+use super::{
+  claims::Audience,
+  config::{AudienceMode, Severity},
+  jwt_decoder::{is_claim_error, Decoded, Payload, Validity},
+  utils::JWTError,
+};
+
+/// Contribution of a single check to the summary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Clause {
+  Pass(String),
+  Fail(String),
+  /// a check that didn't run, with the reason, like `exp (no exp claim)`
+  Skip(String),
+}
+
+/// One line verdict over all the checks relevant for a token. Failures are listed in the order
+/// they were added, so more important checks go first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+  pub clauses: Vec<Clause>,
+  pub signature_verified: bool,
+  /// policy to never treat a token with an unverified signature as valid
+  pub require_signature: bool,
+}
+
+impl Summary {
+  pub fn pass(&mut self, clause: impl Into<String>) {
+    self.clauses.push(Clause::Pass(clause.into()));
+  }
+
+  pub fn fail(&mut self, clause: impl Into<String>) {
+    self.clauses.push(Clause::Fail(clause.into()));
+  }
+
+  pub fn skip(&mut self, clause: impl Into<String>) {
+    self.clauses.push(Clause::Skip(clause.into()));
+  }
+
+  /// the checks that didn't run, like `skipped: exp (no exp claim)`, `None` when all of them ran
+  pub fn skipped(&self) -> Option<String> {
+    let skipped = self
+      .clauses
+      .iter()
+      .filter_map(|c| match c {
+        Clause::Skip(text) => Some(text.as_str()),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    (!skipped.is_empty()).then(|| format!("skipped: {}", skipped.join(", ")))
+  }
+
+  /// the verdict over the checks that ran, without the skipped ones
+  pub fn verdict(&self) -> String {
+    let clauses = |pass: bool, sep: &str| {
+      self
+        .clauses
+        .iter()
+        .filter_map(|c| match c {
+          Clause::Pass(text) if pass => Some(text.as_str()),
+          Clause::Fail(text) if !pass => Some(text.as_str()),
+          _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(sep)
+    };
+    let (passed, failed) = (clauses(true, ", "), clauses(false, "; "));
+
+    if failed.is_empty() {
+      return format!("✓ Token valid: {passed}");
+    }
+    let prefix = if self.is_unverified() {
+      "✗ Token INVALID (unverified): "
+    } else {
+      "✗ Token invalid: "
+    };
+    if passed.is_empty() {
+      format!("{prefix}{failed}")
+    } else {
+      format!("{prefix}{passed}, but {failed}")
+    }
+  }
+
+  pub fn is_valid(&self) -> bool {
+    !self.clauses.iter().any(|c| matches!(c, Clause::Fail(_)))
+  }
+
+  /// the signature is required but wasn't verified
+  pub fn is_unverified(&self) -> bool {
+    self.require_signature && !self.signature_verified
+  }
+
+  /// adds a clause for the `aud` claim when audiences are expected. A missing audience only fails
+  /// the token with [`Severity::Error`], a warning is left to the advisories
+  pub fn check_audience(
+    &mut self,
+    payload: &Payload,
+    expected: &[String],
+    mode: AudienceMode,
+    severity: Severity,
+  ) {
+    if expected.is_empty() {
+      return;
+    }
+    let missing = missing_audiences(payload, expected, mode);
+    if missing.is_empty() {
+      self.pass("aud OK");
+    } else if severity == Severity::Error {
+      self.fail(format!("aud missing: [{}]", missing.join(", ")));
+    }
+  }
+
+  /// adds a clause for the `iss` claim when an issuer is expected. A mismatch only fails the token
+  /// with [`Severity::Error`], like for the audience
+  pub fn check_issuer(&mut self, payload: &Payload, expected: Option<&str>, severity: Severity) {
+    let Some(expected) = expected else {
+      return;
+    };
+    match issuer_mismatch(payload, expected) {
+      None => self.pass("iss OK"),
+      Some(mismatch) if severity == Severity::Error => self.fail(mismatch),
+      Some(_) => {}
+    }
+  }
+
+  /// fails a token whose `jti` is in the `denylist`, of tokens seen before. The check is skipped
+  /// without a `jti` and doesn't run without a denylist
+  pub fn check_jti(&mut self, payload: &Payload, denylist: Option<&BTreeSet<String>>) {
+    let Some(denylist) = denylist else {
+      return;
+    };
+    match payload.0.get("jti") {
+      Some(Value::String(jti)) if denylist.contains(jti) => {
+        self.fail(format!("jti `{jti}` is denylisted, a replay"))
+      }
+      Some(Value::String(_)) => self.pass("jti OK"),
+      _ => self.skip("jti (no jti claim)"),
+    }
+  }
+
+  /// the first failed clause of the `aud`, `iss` and `jti` checks with its claim, like
+  /// `("aud", "aud missing: [web]")`
+  pub fn claim_failure(&self) -> Option<(&'static str, &str)> {
+    self.clauses.iter().find_map(|c| match c {
+      Clause::Fail(text) if text.starts_with("aud ") => Some(("aud", text.as_str())),
+      Clause::Fail(text) if text.starts_with("iss ") => Some(("iss", text.as_str())),
+      Clause::Fail(text) if text.starts_with("jti ") => Some(("jti", text.as_str())),
+      _ => None,
+    })
+  }
+}
+
+/// expected audiences that aren't in the `aud` claim, a string or an array of strings. With
+/// [`AudienceMode::Any`] none are missing as soon as one of them is there
+pub fn missing_audiences(
+  payload: &Payload,
+  expected: &[String],
+  mode: AudienceMode,
+) -> Vec<String> {
+  let aud = payload.0.get("aud").and_then(Audience::from_value);
+  let missing = expected
+    .iter()
+    .filter(|e| !aud.as_ref().is_some_and(|aud| aud.contains(e)))
+    .cloned()
+    .collect::<Vec<_>>();
+  match mode {
+    AudienceMode::Any if missing.len() < expected.len() => vec![],
+    _ => missing,
+  }
+}
+
+/// how the `iss` claim differs from the expected issuer, `None` when it's the one
+pub fn issuer_mismatch(payload: &Payload, expected: &str) -> Option<String> {
+  match payload.0.get("iss") {
+    Some(Value::String(iss)) if iss == expected => None,
+    Some(Value::String(iss)) => Some(format!("iss `{iss}` is not `{expected}`")),
+    Some(iss) => Some(format!("iss {iss} is not `{expected}`")),
+    None => Some("iss missing".into()),
+  }
+}
+
+/// the IDs of a `--jti-denylist` file, one per line. Blank lines and lines starting with `#` are
+/// skipped
+pub fn parse_jti_denylist(content: &str) -> BTreeSet<String> {
+  content
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .map(String::from)
+    .collect()
+}
+
+impl fmt::Display for Summary {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.verdict())?;
+    match self.skipped() {
+      Some(skipped) => write!(f, "; {skipped}"),
+      None => Ok(()),
+    }
+  }
+}
+
+/// aggregates the signature and time claim checks of a decoded token. Time claims are checked
+/// against `now` allowing `leeway` seconds of clock skew
+pub fn summarize(
+  decoded: &Decoded,
+  has_secret: bool,
+  ignore_exp: bool,
+  leeway: i64,
+  now: i64,
+) -> Summary {
+  let mut summary = Summary::default();
+
+  match &decoded.validity {
+    Validity::Verified => summary.pass("signature OK"),
+    // claims are only validated once the signature matched
+    Validity::Unverified(JWTError::External(e, _)) if is_claim_error(e.kind()) => {
+      summary.pass("signature OK")
+    }
+    Validity::Unverified(JWTError::External(e, _))
+      if has_secret && *e.kind() == ErrorKind::InvalidSignature =>
+    {
+      summary.fail("signature invalid")
+    }
+    Validity::Unverified(_) => summary.fail("signature not verified"),
+  }
+  // the signature clause is the only one so far
+  summary.signature_verified = summary.is_valid();
+
+  if decoded.bare_payload.is_some() {
+    // there are no time claims to check
+    summary.fail("payload not a JSON object");
+    return summary;
+  }
+
+  match decoded.payload.timestamp("exp") {
+    Some(exp) if exp + leeway < now => {
+      let clause = format!("expired {} ago", human_duration(now - exp));
+      if ignore_exp {
+        summary.pass(format!("{clause} (ignored)"));
+      } else {
+        summary.fail(clause);
+      }
+    }
+    Some(exp) => summary.pass(format!("expires in {}", human_duration(exp - now))),
+    None if !ignore_exp => summary.fail("missing exp"),
+    None => summary.skip("exp (no exp claim)"),
+  }
+
+  if let Some(nbf) = decoded.payload.timestamp("nbf") {
+    if nbf - leeway > now {
+      summary.fail(format!(
+        "not valid for another {}",
+        human_duration(nbf - now)
+      ));
+    }
+  }
+
+  summary
+}
+
+/// Verdict on a single time claim, shown next to the claim in the payload like `✗ expired`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimMarker {
+  pub claim: &'static str,
+  pub valid: bool,
+  /// the symbol first, so that the verdict doesn't depend on color
+  pub text: &'static str,
+}
+
+/// markers of the `exp`, `nbf` and `iat` claims the payload has, checked against `now` with
+/// `leeway` seconds of clock skew like [`summarize`] does
+pub fn time_claim_markers(payload: &Payload, leeway: i64, now: i64) -> Vec<ClaimMarker> {
+  let marker = |claim, valid, invalid_text| ClaimMarker {
+    claim,
+    valid,
+    text: if valid { "✓" } else { invalid_text },
+  };
+  let mut markers = vec![];
+  if let Some(exp) = payload.timestamp("exp") {
+    markers.push(marker("exp", exp + leeway >= now, "✗ expired"));
+  }
+  if let Some(nbf) = payload.timestamp("nbf") {
+    markers.push(marker("nbf", nbf - leeway <= now, "✗ not yet"));
+  }
+  if let Some(iat) = payload.timestamp("iat") {
+    markers.push(marker("iat", iat - leeway <= now, "✗ in the future"));
+  }
+  markers
+}
+
+/// plain English paragraph about the token for newcomers, like `This is an RS256-signed JWT issued
+/// by https://issuer with key ID abc, …, expires in 55m. Signature not verified (no key provided).`
+/// The time claims are compared to `now`
+pub fn explain(decoded: &Decoded, has_secret: bool, now: i64) -> String {
+  let header = decoded.signing_header();
+  let kind = if decoded.sd_jwt.is_some() {
+    "SD-JWT"
+  } else {
+    "JWT"
+  };
+  let mut text = match header.alg.as_str() {
+    "none" => format!("This is an unsecured {kind} (alg: none)"),
+    alg if alg.starts_with(['E', 'H', 'R']) => format!("This is an {alg}-signed {kind}"),
+    alg => format!("This is a {alg}-signed {kind}"),
+  };
+
+  // time claims are read on their own, they may have been converted to dates for display
+  let payload = &decoded.payload;
+  let mut claims = payload.0.clone();
+  for claim in ["exp", "nbf", "iat"] {
+    claims.shift_remove(claim);
+  }
+  let claims = Payload(claims).standard_claims().unwrap_or_default();
+  let mut clauses = vec![];
+  match (&claims.iss, &header.kid) {
+    (Some(iss), Some(kid)) => clauses.push(format!("issued by {iss} with key ID {kid}")),
+    (Some(iss), None) => clauses.push(format!("issued by {iss}")),
+    (None, Some(kid)) => clauses.push(format!("signed with key ID {kid}")),
+    (None, None) => {}
+  }
+  if let Some(aud) = &claims.aud {
+    clauses.push(format!(
+      "for audience {}",
+      aud.iter().collect::<Vec<_>>().join(" and ")
+    ));
+  }
+  if let Some(sub) = &claims.sub {
+    clauses.push(format!("subject {sub}"));
+  }
+  match payload.timestamp("iat") {
+    Some(iat) if iat > now => clauses.push(format!("issued in {}", human_duration(iat - now))),
+    Some(iat) => clauses.push(format!("issued {} ago", human_duration(now - iat))),
+    None => {}
+  }
+  if let Some(nbf) = payload.timestamp("nbf").filter(|nbf| *nbf > now) {
+    clauses.push(format!(
+      "not valid for another {}",
+      human_duration(nbf - now)
+    ));
+  }
+  match payload.timestamp("exp") {
+    Some(exp) if exp < now => clauses.push(format!("expired {} ago", human_duration(now - exp))),
+    Some(exp) => clauses.push(format!("expires in {}", human_duration(exp - now))),
+    None if decoded.bare_payload.is_none() => clauses.push("never expires".into()),
+    None => {}
+  }
+  // the claims other than the registered ones
+  let custom = claims.extra.len();
+  if custom > 0 {
+    let plural = if custom == 1 { "" } else { "s" };
+    clauses.push(format!("plus {custom} custom claim{plural}"));
+  }
+  if !clauses.is_empty() {
+    text = format!("{text} {}", clauses.join(", "));
+  }
+  text.push('.');
+  if decoded.bare_payload.is_some() {
+    text.push_str(" Its payload is not a JSON object, so it has no claims.");
+  }
+
+  let signature = match &decoded.validity {
+    Validity::Verified => "Signature verified.".into(),
+    Validity::Unverified(JWTError::External(e, _)) if is_claim_error(e.kind()) => {
+      format!("Signature verified, but the claims were rejected: {e}.")
+    }
+    Validity::Unverified(_) if !has_secret => "Signature not verified (no key provided).".into(),
+    Validity::Unverified(JWTError::External(e, _)) if *e.kind() == ErrorKind::InvalidSignature => {
+      "Signature invalid for the given key.".into()
+    }
+    Validity::Unverified(e) => format!("Signature not verified: {e}."),
+  };
+  format!("{text} {signature}")
+}
+
+/// rough duration in the largest fitting unit, e.g. `2m` or `3d`
+fn human_duration(secs: i64) -> String {
+  let secs = secs.max(0);
+  match secs {
+    0..=59 => format!("{secs}s"),
+    60..=3599 => format!("{}m", secs / 60),
+    3600..=86399 => format!("{}h", secs / 3600),
+    _ => format!("{}d", secs / 86400),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use jsonwebtoken::{encode, EncodingKey, Header};
+  use serde_json::{json, Value};
+
+  use super::*;
+  use crate::app::jwt_decoder::{decode, DecodeOptions};
+
+  const NOW: i64 = 1_700_000_000;
+
+  fn decoded(claims: Value, secret: &str, ignore_exp: bool) -> Decoded {
+    let token = encode(
+      &Header::default(),
+      &claims,
+      &EncodingKey::from_secret(b"secret"),
+    )
+    .unwrap();
+    decode(
+      &token,
+      &DecodeOptions {
+        secret: secret.into(),
+        ignore_exp,
+        ..DecodeOptions::default()
+      },
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn test_summarize_valid_token() {
+    let summary = summarize(
+      &decoded(json!({ "exp": NOW + 300 }), "secret", true),
+      true,
+      false,
+      60,
+      NOW,
+    );
+    assert!(summary.is_valid());
+    assert_eq!(
+      summary.to_string(),
+      "✓ Token valid: signature OK, expires in 5m"
+    );
+  }
+
+  #[test]
+  fn test_summarize_invalid_token() {
+    let summary = summarize(
+      &decoded(
+        json!({ "exp": NOW - 120, "nbf": NOW + 7200 }),
+        "secret",
+        true,
+      ),
+      true,
+      false,
+      60,
+      NOW,
+    );
+    assert!(!summary.is_valid());
+    assert_eq!(
+      summary.to_string(),
+      "✗ Token invalid: signature OK, but expired 2m ago; not valid for another 2h"
+    );
+
+    let summary = summarize(&decoded(json!({}), "wrong", true), true, false, 60, NOW);
+    assert_eq!(
+      summary.to_string(),
+      "✗ Token invalid: signature invalid; missing exp"
+    );
+
+    let summary = summarize(&decoded(json!({}), "", true), false, true, 60, NOW);
+    assert_eq!(
+      summary.to_string(),
+      "✗ Token invalid: signature not verified; skipped: exp (no exp claim)"
+    );
+  }
+
+  #[test]
+  fn test_summarize_with_required_signature() {
+    let mut summary = summarize(&decoded(json!({}), "", true), false, true, 60, NOW);
+    summary.require_signature = true;
+    assert!(summary.is_unverified());
+    assert_eq!(
+      summary.to_string(),
+      "✗ Token INVALID (unverified): signature not verified; skipped: exp (no exp claim)"
+    );
+
+    // verified tokens failing other checks are shown as before
+    let mut summary = summarize(&decoded(json!({}), "secret", true), true, false, 60, NOW);
+    summary.require_signature = true;
+    assert!(!summary.is_unverified());
+    assert_eq!(
+      summary.to_string(),
+      "✗ Token invalid: signature OK, but missing exp"
+    );
+  }
+
+  #[test]
+  fn test_missing_audiences() {
+    let expected = ["api".to_string(), "web".to_string()];
+    let payload = |aud: Value| Payload(serde_json::from_value(json!({ "aud": aud })).unwrap());
+
+    let aud = payload(json!("api"));
+    assert!(missing_audiences(&aud, &expected, AudienceMode::Any).is_empty());
+    assert_eq!(
+      missing_audiences(&aud, &expected, AudienceMode::All),
+      vec!["web".to_string()]
+    );
+
+    let aud = payload(json!(["web", "api", "other"]));
+    assert!(missing_audiences(&aud, &expected, AudienceMode::All).is_empty());
+
+    for aud in [
+      payload(json!([])),
+      payload(json!("")),
+      Payload(Default::default()),
+    ] {
+      assert_eq!(
+        missing_audiences(&aud, &expected, AudienceMode::Any),
+        expected.to_vec()
+      );
+    }
+  }
+
+  #[test]
+  fn test_summarize_with_audience() {
+    let mut summary = summarize(
+      &decoded(json!({ "exp": NOW + 300, "aud": ["api"] }), "secret", true),
+      true,
+      false,
+      60,
+      NOW,
+    );
+    let payload = decoded(json!({ "aud": ["api"] }), "secret", true).payload;
+    summary.check_audience(
+      &payload,
+      &["api".into(), "web".into(), "admin".into()],
+      AudienceMode::All,
+      Severity::Error,
+    );
+    assert_eq!(
+      summary.to_string(),
+      "✗ Token invalid: signature OK, expires in 5m, but aud missing: [web, admin]"
+    );
+    assert_eq!(
+      summary.claim_failure(),
+      Some(("aud", "aud missing: [web, admin]"))
+    );
+
+    // a warning leaves the token valid
+    let mut summary = Summary::default();
+    summary.check_audience(
+      &payload,
+      &["web".into()],
+      AudienceMode::All,
+      Severity::Warning,
+    );
+    assert!(summary.is_valid());
+    assert_eq!(summary.claim_failure(), None);
+
+    let mut summary = Summary::default();
+    summary.check_audience(&payload, &[], AudienceMode::All, Severity::Error);
+    assert!(summary.clauses.is_empty());
+  }
+
+  #[test]
+  fn test_summarize_with_issuer() {
+    let payload = decoded(json!({ "iss": "https://staging" }), "secret", true).payload;
+    let mut summary = Summary::default();
+    summary.check_issuer(&payload, Some("https://staging"), Severity::Error);
+    assert_eq!(summary.to_string(), "✓ Token valid: iss OK");
+
+    let mut summary = Summary::default();
+    summary.check_issuer(&payload, Some("https://prod"), Severity::Error);
+    assert_eq!(
+      summary.claim_failure(),
+      Some(("iss", "iss `https://staging` is not `https://prod`"))
+    );
+    let mut summary = Summary::default();
+    summary.check_issuer(&payload, Some("https://prod"), Severity::Warning);
+    assert!(summary.is_valid());
+
+    let payload = decoded(json!({ "iss": 1 }), "secret", true).payload;
+    assert_eq!(
+      issuer_mismatch(&payload, "https://prod"),
+      Some("iss 1 is not `https://prod`".into())
+    );
+    let payload = decoded(json!({}), "secret", true).payload;
+    assert_eq!(
+      issuer_mismatch(&payload, "https://prod"),
+      Some("iss missing".into())
+    );
+
+    let mut summary = Summary::default();
+    summary.check_issuer(&payload, None, Severity::Error);
+    assert!(summary.clauses.is_empty());
+  }
+
+  #[test]
+  fn test_summarize_with_jti_denylist() {
+    let denylist = parse_jti_denylist(
+      "# seen on 2024-05-01
+ a1 
+
+b2
+",
+    );
+    assert_eq!(denylist, BTreeSet::from(["a1".into(), "b2".into()]));
+
+    let payload = decoded(json!({ "jti": "c3" }), "secret", true).payload;
+    let mut summary = Summary::default();
+    summary.check_jti(&payload, Some(&denylist));
+    assert_eq!(summary.to_string(), "✓ Token valid: jti OK");
+
+    let payload = decoded(json!({ "jti": "b2" }), "secret", true).payload;
+    let mut summary = Summary::default();
+    summary.check_jti(&payload, Some(&denylist));
+    assert_eq!(
+      summary.claim_failure(),
+      Some(("jti", "jti `b2` is denylisted, a replay"))
+    );
+
+    let payload = decoded(json!({}), "secret", true).payload;
+    let mut summary = Summary::default();
+    summary.check_jti(&payload, Some(&denylist));
+    assert!(summary.is_valid());
+    assert_eq!(
+      summary.skipped(),
+      Some("skipped: jti (no jti claim)".into())
+    );
+
+    let mut summary = Summary::default();
+    summary.check_jti(&payload, None);
+    assert!(summary.clauses.is_empty());
+  }
+
+  #[test]
+  fn test_summarize_skipped_checks() {
+    let summary = summarize(&decoded(json!({}), "secret", true), true, true, 60, NOW);
+    assert!(summary.is_valid());
+    assert_eq!(summary.verdict(), "✓ Token valid: signature OK");
+    assert_eq!(
+      summary.to_string(),
+      "✓ Token valid: signature OK; skipped: exp (no exp claim)"
+    );
+
+    let mut summary = Summary::default();
+    summary.fail("signature invalid");
+    summary.skip("exp (no exp claim)");
+    assert!(!summary.is_valid());
+    assert_eq!(
+      summary.to_string(),
+      "✗ Token invalid: signature invalid; skipped: exp (no exp claim)"
+    );
+    summary.clauses.pop();
+    assert_eq!(summary.skipped(), None);
+  }
+
+  #[test]
+  fn test_summarize_ignored_exp() {
+    let summary = summarize(
+      &decoded(json!({ "exp": NOW - 3 * 86400 }), "secret", true),
+      true,
+      true,
+      60,
+      NOW,
+    );
+    assert!(summary.is_valid());
+    assert_eq!(
+      summary.to_string(),
+      "✓ Token valid: signature OK, expired 3d ago (ignored)"
+    );
+  }
+
+  #[test]
+  fn test_explain() {
+    let claims = json!({
+      "iss": "https://issuer",
+      "aud": "api",
+      "sub": "user-123",
+      "iat": NOW - 300,
+      "exp": NOW + 3300,
+      "roles": ["admin"],
+      "tenant": "acme",
+    });
+    assert_eq!(
+      explain(&decoded(claims.clone(), "", true), false, NOW),
+      "This is an HS256-signed JWT issued by https://issuer, for audience api, subject user-123, \
+       issued 5m ago, expires in 55m, plus 2 custom claims. Signature not verified (no key provided)."
+    );
+    assert!(explain(&decoded(claims, "secret", true), true, NOW).ends_with(" Signature verified."));
+    assert!(explain(&decoded(json!({}), "other", true), true, NOW)
+      .ends_with("JWT never expires. Signature invalid for the given key."));
+
+    let mut decoded = decoded(json!({ "exp": NOW - 60 }), "", true);
+    decoded.header.alg = "PS256".into();
+    decoded.header.kid = Some("abc".into());
+    assert!(explain(&decoded, false, NOW)
+      .starts_with("This is a PS256-signed JWT signed with key ID abc, expired 1m ago."));
+  }
+
+  #[test]
+  fn test_time_claim_markers() {
+    let payload = decoded(
+      json!({ "exp": NOW - 120, "nbf": NOW + 30, "iat": NOW - 600 }),
+      "secret",
+      true,
+    )
+    .payload;
+    let texts = |leeway| {
+      time_claim_markers(&payload, leeway, NOW)
+        .into_iter()
+        .map(|m| (m.claim, m.valid, m.text))
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(
+      texts(0),
+      [
+        ("exp", false, "✗ expired"),
+        ("nbf", false, "✗ not yet"),
+        ("iat", true, "✓")
+      ]
+    );
+    // within the leeway
+    assert_eq!(
+      texts(120),
+      [("exp", true, "✓"), ("nbf", true, "✓"), ("iat", true, "✓")]
+    );
+    // claims that aren't timestamps have no marker
+    let payload = decoded(json!({ "exp": "soon" }), "secret", true).payload;
+    assert_eq!(time_claim_markers(&payload, 0, NOW), []);
+  }
+}
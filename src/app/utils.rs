@@ -1,14 +1,143 @@
-use std::{fmt, fs, io, str::Utf8Error};
+use std::{
+  collections::HashMap,
+  env, fmt, fs,
+  io::{self, IsTerminal, Read, Write},
+  path::Path,
+  process::{Command, Stdio},
+  str::{from_utf8, Utf8Error},
+  sync::{Mutex, OnceLock},
+};
 
+use base64::{engine::general_purpose::STANDARD, Engine};
 use jsonwebtoken::{
   errors::{Error, ErrorKind},
   jwk, Algorithm, DecodingKey, Header,
 };
+use log::{debug, warn};
+use sha2::{Digest, Sha256};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum JWTError {
   Internal(String),
   External(Error, String),
+  Decode(DecodeError),
+}
+
+/// A segment of a JWT
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Segment {
+  Header,
+  Payload,
+  Signature,
+}
+
+impl fmt::Display for Segment {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      Segment::Header => "header",
+      Segment::Payload => "payload",
+      Segment::Signature => "signature",
+    };
+    write!(f, "{name}")
+  }
+}
+
+/// Why a token or the key to verify it with couldn't be read. The variants are kept stable so that
+/// callers can match on the cause
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DecodeError {
+  /// number of `.` separated segments found instead of 3
+  WrongSegmentCount(usize),
+  /// the input is JSON, like a decoded payload pasted instead of the token
+  JsonInput,
+  InvalidBase64(Segment),
+  /// the segment's length can't be that of base64, it's most likely cut off
+  TruncatedBase64(Segment),
+  InvalidHeaderJson(String),
+  InvalidPayloadJson(String),
+  UnsupportedAlg(String),
+  KeyParse(String),
+  /// number of the SD-JWT disclosure, counted from 1, and why it can't be read
+  InvalidDisclosure(usize, String),
+  /// the payload of a `zip: DEF` token couldn't be inflated
+  InvalidCompressedPayload(String),
+  /// the token is longer than the max size in bytes, it isn't decoded
+  TokenTooLarge(usize),
+}
+
+impl fmt::Display for DecodeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      DecodeError::WrongSegmentCount(count) => write!(
+        f,
+        "Expected 3 segments `header.payload.signature` but the token has {count}"
+      ),
+      DecodeError::JsonInput => write!(
+        f,
+        "This looks like JSON, not a JWT: paste the encoded `header.payload.signature` token"
+      ),
+      DecodeError::InvalidBase64(segment) => {
+        write!(f, "The token {segment} is not valid base64url")
+      }
+      DecodeError::TruncatedBase64(segment) => write!(
+        f,
+        "The token {segment} has an invalid base64 length, the token was likely truncated"
+      ),
+      DecodeError::InvalidHeaderJson(e) => write!(f, "The token header is not valid: {e}"),
+      DecodeError::InvalidPayloadJson(e) => {
+        write!(f, "The token payload is not valid JSON: {e}")
+      }
+      DecodeError::UnsupportedAlg(alg) => write!(f, "The `alg` {alg} is not supported"),
+      DecodeError::KeyParse(e) => write!(f, "{e}"),
+      DecodeError::InvalidDisclosure(index, e) => {
+        write!(f, "The SD-JWT disclosure {index} is not valid, {e}")
+      }
+      DecodeError::InvalidCompressedPayload(e) => write!(
+        f,
+        "The token payload is not valid DEFLATE compressed data (zip: DEF): {e}"
+      ),
+      DecodeError::TokenTooLarge(max) => write!(
+        f,
+        "token exceeds max size ({max} bytes); adjust with --max-token-size"
+      ),
+    }
+  }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl DecodeError {
+  /// the segment that couldn't be read, `None` when it's the token as a whole
+  pub fn segment(&self) -> Option<Segment> {
+    match self {
+      DecodeError::WrongSegmentCount(_)
+      | DecodeError::JsonInput
+      | DecodeError::InvalidDisclosure(..)
+      | DecodeError::TokenTooLarge(_) => None,
+      DecodeError::InvalidBase64(segment) | DecodeError::TruncatedBase64(segment) => Some(*segment),
+      DecodeError::InvalidHeaderJson(_) | DecodeError::UnsupportedAlg(_) => Some(Segment::Header),
+      DecodeError::InvalidPayloadJson(_) | DecodeError::InvalidCompressedPayload(_) => {
+        Some(Segment::Payload)
+      }
+      DecodeError::KeyParse(_) => Some(Segment::Signature),
+    }
+  }
+}
+
+impl JWTError {
+  /// the segment a decode error is about, `None` when it's the token as a whole
+  pub fn segment(&self) -> Option<Segment> {
+    match self {
+      JWTError::Decode(e) => e.segment(),
+      JWTError::Internal(_) | JWTError::External(..) => None,
+    }
+  }
+}
+
+impl From<DecodeError> for JWTError {
+  fn from(value: DecodeError) -> Self {
+    JWTError::Decode(value)
+  }
 }
 
 pub type JWTResult<T> = Result<T, JWTError>;
@@ -49,6 +178,7 @@ impl fmt::Display for JWTError {
     match self {
       JWTError::Internal(err) => write!(f, "{err}"),
       JWTError::External(err, msg) => write!(f, "{msg}: {err}"),
+      JWTError::Decode(err) => write!(f, "{err}"),
     }
   }
 }
@@ -61,22 +191,89 @@ pub enum SecretType {
   Plain,
 }
 
+/// key verifying tokens of the given `alg` and the formats it's read from, `None` for unsecured
+/// tokens and unknown algorithms
+fn verifying_key(alg: &str) -> Option<(&'static str, &'static str)> {
+  match alg {
+    "HS256" | "HS384" | "HS512" => Some((
+      "the shared secret",
+      "plain text, 'b64:' base64, 'hex:' hex or JWKS",
+    )),
+    "RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512" => {
+      Some(("an RSA public key", "PEM/DER/JWKS"))
+    }
+    "ES256" => Some(("a P-256 EC public key", "PEM/DER/JWKS")),
+    "ES384" => Some(("a P-384 EC public key", "PEM/DER/JWKS")),
+    "ES256K" => Some(("a secp256k1 EC public key", "PEM/DER")),
+    "EdDSA" => Some(("an Ed25519 public key", "PEM/DER/JWKS")),
+    _ => None,
+  }
+}
+
+/// short description of the key material needed to verify and sign tokens of the given `alg`
+pub fn key_requirements(alg: &str) -> Option<String> {
+  if alg == "none" {
+    return Some(format!(
+      "{alg} is unsecured, there is no signature to verify"
+    ));
+  }
+  let (key, formats) = verifying_key(alg)?;
+  Some(match alg {
+    "HS256" | "HS384" | "HS512" => format!("{alg} needs {key} to verify and sign ({formats})"),
+    // signing ES256K tokens isn't supported
+    "ES256K" => format!("{alg} needs {key} ({formats}) to verify"),
+    _ => format!("{alg} needs {key} ({formats}) to verify; private key to sign"),
+  })
+}
+
+/// what to enter to verify a token of the given `alg` while there is no secret, e.g. `Provide an
+/// RSA public key to verify this RS256 token`
+pub fn verify_prompt(alg: &str) -> Option<String> {
+  let (key, _) = verifying_key(alg)?;
+  Some(format!("Provide {key} to verify this {alg} token"))
+}
+
+/// prefix of a secret read as a JWKS whatever the algorithm, e.g. `jwks:@keys/jwks.txt`
+pub const JWKS_PREFIX: &str = "jwks:";
+
+/// prefix of a hex encoded HMAC secret, e.g. `hex:736563726574`
+pub const HEX_PREFIX: &str = "hex:";
+
+/// prefix of a base64 encoded HMAC secret, e.g. `b64:c2VjcmV0`
+pub const B64_PREFIX: &str = "b64:";
+
 pub fn get_secret_from_file_or_input(
   alg: &Algorithm,
   secret_string: &str,
 ) -> (JWTResult<Vec<u8>>, SecretType) {
-  return match alg {
+  if let Some(jwks) = secret_string.strip_prefix(JWKS_PREFIX) {
+    // keys are only read from disk, nothing is fetched
+    let content = if jwks.starts_with('@') {
+      slurp_file(strip_leading_symbol(jwks)).map_err(JWTError::from)
+    } else {
+      Ok(jwks.as_bytes().to_vec())
+    };
+    return (content, SecretType::Jwks);
+  }
+  match alg {
     Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
       if secret_string.starts_with('@') {
+        let file = strip_leading_symbol(secret_string);
+        let content = slurp_file(file.clone()).map_err(JWTError::from);
+        // a saved JWKS may have any extension, streamed secrets have none
+        let is_jwks = has_file_extension(secret_string, "json")
+          || content
+            .as_ref()
+            .is_ok_and(|content| from_utf8(content).is_ok_and(is_jwks_text));
         (
-          slurp_file(strip_leading_symbol(secret_string)).map_err(JWTError::from),
-          if secret_string.ends_with(".json") {
+          content,
+          if is_jwks {
             SecretType::Jwks
           } else {
             SecretType::Plain
           },
         )
-      } else if secret_string.starts_with("b64:") {
+      } else if secret_string.starts_with(B64_PREFIX) {
         (
           Ok(
             secret_string
@@ -88,80 +285,485 @@ pub fn get_secret_from_file_or_input(
           ),
           SecretType::B64,
         )
+      } else if let Some(hex) = secret_string.strip_prefix(HEX_PREFIX) {
+        let secret =
+          decode_hex(hex.trim()).ok_or_else(|| JWTError::Internal("invalid hex secret".into()));
+        (secret, SecretType::Plain)
+      } else if is_jwks_text(secret_string) {
+        // a pasted JWKS, plain secrets are never JSON objects with `keys`
+        (Ok(secret_string.as_bytes().to_owned()), SecretType::Jwks)
       } else {
         (Ok(secret_string.as_bytes().to_owned()), SecretType::Plain)
       }
     }
     _ => {
       if secret_string.starts_with('@') {
-        (
-          slurp_file(strip_leading_symbol(secret_string)).map_err(JWTError::from),
-          get_secret_file_type(secret_string),
-        )
+        let file = strip_leading_symbol(secret_string);
+        let content = slurp_file(file.clone()).map_err(JWTError::from);
+        let secret_type = match &content {
+          Ok(content) if from_utf8(content).is_ok_and(is_jwks_text) => SecretType::Jwks,
+          Ok(content) if is_streamed(&file) => get_streamed_secret_type(content),
+          _ => get_secret_file_type(secret_string),
+        };
+        (content, secret_type)
       } else {
         // allows to read JWKS from argument (e.g. output of 'curl https://auth.domain.com/jwks.json')
         (Ok(secret_string.as_bytes().to_vec()), SecretType::Jwks)
       }
     }
-  };
+  }
 }
 
+/// the path of an `@path` secret, taken literally as an OS path so that drive letters,
+/// backslashes and UNC paths like `@\\server\share\key.pem` work on Windows. Quotes around it,
+/// as added when copying a path in the Windows explorer, are removed
 pub fn strip_leading_symbol(secret_string: &str) -> String {
-  secret_string.chars().skip(1).collect::<String>()
+  let path = secret_string.chars().skip(1).collect::<String>();
+  match path.strip_prefix('"').and_then(|p| p.strip_suffix('"')) {
+    Some(unquoted) => unquoted.into(),
+    None => path,
+  }
+}
+
+/// whether the `@path` secret has the extension, ignoring case as Windows does
+pub fn has_file_extension(secret_string: &str, extension: &str) -> bool {
+  Path::new(&strip_leading_symbol(secret_string))
+    .extension()
+    .is_some_and(|e| e.eq_ignore_ascii_case(extension))
 }
 
-pub fn decoding_key_from_jwks_secret(
+/// keys of a JWKS secret to verify the token with: the key matching the header's `kid` or, when
+/// there is none, every key usable for the token's `alg`
+pub fn decoding_keys_from_jwks_secret(
   secret: &[u8],
+  alg: &Algorithm,
   header: Option<Header>,
-) -> JWTResult<DecodingKey> {
-  if let Some(h) = header {
-    return match parse_jwks(secret) {
-      Some(jwks) => decoding_key_from_jwks(jwks, &h),
-      None => Err(JWTError::Internal("Invalid jwks secret format".to_string())),
-    };
+) -> JWTResult<Vec<DecodingKey>> {
+  let Some(h) = header else {
+    return Err(DecodeError::KeyParse("Invalid jwt header for jwks secret".into()).into());
+  };
+  match parse_jwks(secret) {
+    Some(jwks) => decoding_keys_from_jwks(jwks, alg, &h),
+    None => Err(DecodeError::KeyParse("Invalid jwks secret format".into()).into()),
   }
-  Err(JWTError::Internal(
-    "Invalid jwt header for jwks secret".to_string(),
-  ))
 }
 
+/// whether the plain text secret is a JWKS, i.e. a JSON object with a `keys` array
+pub fn is_jwks_text(secret: &str) -> bool {
+  serde_json::from_str::<serde_json::Value>(secret)
+    .is_ok_and(|value| value.get("keys").is_some_and(|keys| keys.is_array()))
+}
+
+/// reads a secret file. `-` is STDIN and `fd:N` an inherited file descriptor, as used by secret
+/// managers to pass secrets without touching the disk. Those can only be read once, so their
+/// content is kept for the following decodes
 pub fn slurp_file(file_name: String) -> io::Result<Vec<u8>> {
-  fs::read(file_name)
+  let content = read_file(&file_name);
+  match &content {
+    Ok(content) => debug!("read {} bytes of key from {file_name}", content.len()),
+    Err(e) => warn!("unable to read the key from {file_name}: {e}"),
+  }
+  content
 }
 
-fn decoding_key_from_jwks(jwks: jwk::JwkSet, header: &Header) -> JWTResult<DecodingKey> {
-  let kid = match &header.kid {
-    Some(k) => k.to_owned(),
+fn read_file(file_name: &str) -> io::Result<Vec<u8>> {
+  if !is_streamed(file_name) {
+    return fs::read(file_name);
+  }
+  static STREAMED: OnceLock<Mutex<HashMap<String, Vec<u8>>>> = OnceLock::new();
+  let mut streamed = STREAMED
+    .get_or_init(Default::default)
+    .lock()
+    .unwrap_or_else(|e| e.into_inner());
+  if let Some(content) = streamed.get(file_name) {
+    return Ok(content.clone());
+  }
+  let content = match file_name.strip_prefix("fd:") {
+    Some(fd) => {
+      let fd = fd.parse::<u32>().map_err(|_| {
+        io::Error::new(
+          io::ErrorKind::InvalidInput,
+          format!("`{fd}` is not a file descriptor"),
+        )
+      })?;
+      fs::read(format!("/dev/fd/{fd}"))?
+    }
+    // reading a terminal would block until the UI is left
+    None if io::stdin().is_terminal() => {
+      return Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "STDIN is a terminal, pipe the secret into it to use `@-`",
+      ))
+    }
     None => {
-      return Err(JWTError::Internal(
-        "Missing 'kid' from jwt header. Required for jwks secret".to_string(),
-      ));
+      let mut content = vec![];
+      io::stdin().read_to_end(&mut content)?;
+      content
     }
   };
+  streamed.insert(file_name.into(), content.clone());
+  Ok(content)
+}
 
-  let jwk = match jwks.find(&kid) {
-    Some(j) => j,
-    None => {
-      return Err(JWTError::Internal(format!(
-        "No jwk found for 'kid' {kid:?}",
-      )));
+/// the secret file is STDIN or a file descriptor rather than a path
+pub fn is_streamed(file_name: &str) -> bool {
+  file_name == "-" || file_name.starts_with("fd:")
+}
+
+/// the secret is read from STDIN, as `@-` or `@fd:0`, also of a JWKS. It can't be when the
+/// tokens or claims are read from STDIN already
+pub fn reads_stdin(secret: &str) -> bool {
+  let secret = secret.strip_prefix(JWKS_PREFIX).unwrap_or(secret);
+  matches!(secret.strip_prefix('@'), Some("-" | "fd:0"))
+}
+
+/// URL of a web decoder for the token, `{token}` in `template` is replaced by it or it's appended
+pub fn web_decoder_url(template: &str, token: &str) -> String {
+  if template.contains("{token}") {
+    template.replace("{token}", token)
+  } else {
+    format!("{template}{token}")
+  }
+}
+
+/// `curl` command calling `url` with the token as bearer. The URL is quoted for the shell
+pub fn curl_command(url: &str, token: &str) -> String {
+  format!(
+    "curl -H \"Authorization: Bearer {token}\" '{}'",
+    url.replace('\'', r"'\''")
+  )
+}
+
+/// text content of the system clipboard
+pub fn read_clipboard() -> Result<String, String> {
+  use copypasta::{ClipboardContext, ClipboardProvider};
+
+  let content = ClipboardContext::new()
+    .map_err(|e| format!("Unable to obtain clipboard: {e}"))?
+    .get_contents()
+    .map_err(|e| format!("Unable to read clipboard contents: {e}"));
+  match &content {
+    Ok(content) => debug!("read {} bytes from the clipboard", content.len()),
+    Err(e) => warn!("{e}"),
+  }
+  content
+}
+
+/// terminals ignore OSC 52 sequences above about this many bytes of base64
+const OSC52_MAX_SIZE: usize = 100_000;
+
+/// OSC 52 escape sequence setting the terminal's clipboard to `content`, wrapped for tmux to
+/// pass it on to the terminal
+pub fn osc52_sequence(content: &str, tmux: bool) -> String {
+  let osc52 = format!("\x1b]52;c;{}\x07", STANDARD.encode(content));
+  if tmux {
+    format!("\x1bPtmux;{}\x1b\\", osc52.replace('\x1b', "\x1b\x1b"))
+  } else {
+    osc52
+  }
+}
+
+/// copies `content` through the terminal with OSC 52, for sessions without a system clipboard
+/// like over SSH. Whether the terminal supports it can't be told
+pub fn write_osc52(content: &str) -> io::Result<()> {
+  let sequence = osc52_sequence(content, env::var_os("TMUX").is_some());
+  if sequence.len() > OSC52_MAX_SIZE {
+    return Err(io::Error::other("too large for the terminal's clipboard"));
+  }
+  let mut stdout = io::stdout().lock();
+  if !stdout.is_terminal() {
+    return Err(io::Error::other("STDOUT is not a terminal"));
+  }
+  // written at once between two frames, the sequence moves no cursor and prints nothing
+  stdout.write_all(sequence.as_bytes())?;
+  stdout.flush()
+}
+
+/// opens `url` with the default browser of the OS
+pub fn open_in_browser(url: &str) -> io::Result<()> {
+  let mut command = if cfg!(target_os = "macos") {
+    Command::new("open")
+  } else if cfg!(target_os = "windows") {
+    // unlike `start`, this doesn't interpret `&` in the URL
+    let mut command = Command::new("rundll32");
+    command.arg("url.dll,FileProtocolHandler");
+    command
+  } else {
+    Command::new("xdg-open")
+  };
+  command
+    .arg(url)
+    .stdin(Stdio::null())
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .spawn()
+    .map(|_| ())
+}
+
+/// bytes of a hex string like `0a1B`, `None` when it isn't an even number of hex digits
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+  if !hex.len().is_multiple_of(2) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+    return None;
+  }
+  (0..hex.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+    .collect()
+}
+
+/// classic `offset  hex  |ascii|` dump with 16 bytes per line, non-printable bytes are shown as `.`
+pub fn hexdump(bytes: &[u8]) -> String {
+  if bytes.is_empty() {
+    return "(no bytes)".into();
+  }
+  bytes
+    .chunks(16)
+    .enumerate()
+    .map(|(line, chunk)| {
+      let hex = chunk
+        .iter()
+        .enumerate()
+        // an extra space after 8 bytes, like `hexdump -C`
+        .map(|(i, b)| match i {
+          8 => format!(" {b:02x}"),
+          _ => format!("{b:02x}"),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+      let ascii: String = chunk
+        .iter()
+        .map(|&b| match b {
+          b' '..=b'~' => b as char,
+          _ => '.',
+        })
+        .collect();
+      format!("{:08x}  {hex:<48}  |{ascii}|", line * 16)
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// byte size in the largest fitting unit, e.g. `1.5 MB`
+pub fn human_size(bytes: usize) -> String {
+  const KB: f64 = 1024.0;
+  let size = bytes as f64;
+  if size < KB {
+    format!("{bytes} B")
+  } else if size < KB * KB {
+    format!("{:.1} KB", size / KB)
+  } else {
+    format!("{:.1} MB", size / (KB * KB))
+  }
+}
+
+/// short SHA-256 based fingerprint to tell tokens apart without revealing them
+pub fn token_fingerprint(token: &str) -> String {
+  Sha256::digest(token.as_bytes())
+    .iter()
+    .take(4)
+    .map(|b| format!("{b:02x}"))
+    .collect()
+}
+
+/// decodes `%XX` escapes in the given string. Returns the input as-is if it isn't percent-encoded
+/// or doesn't decode to valid UTF-8
+pub fn percent_decode(value: &str) -> String {
+  let bytes = value.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    let escape = bytes
+      .get(i + 1..i + 3)
+      .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+    if let (b'%', Some(hex)) = (bytes[i], escape) {
+      // two hex digits are valid UTF-8 and a valid byte in base 16
+      decoded.push(u8::from_str_radix(from_utf8(hex).unwrap(), 16).unwrap());
+      i += 3;
+      continue;
     }
+    decoded.push(bytes[i]);
+    i += 1;
+  }
+  String::from_utf8(decoded).unwrap_or_else(|_| value.to_string())
+}
+
+/// Named elliptic curves of EC keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EcCurve {
+  P256,
+  P384,
+  P521,
+  Secp256k1,
+}
+
+impl fmt::Display for EcCurve {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      EcCurve::P256 => write!(f, "P-256"),
+      EcCurve::P384 => write!(f, "P-384"),
+      EcCurve::P521 => write!(f, "P-521"),
+      EcCurve::Secp256k1 => write!(f, "secp256k1"),
+    }
+  }
+}
+
+// DER encoded object identifiers of the named curves
+const EC_CURVE_OIDS: [(&[u8], EcCurve); 4] = [
+  (
+    &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07],
+    EcCurve::P256,
+  ),
+  (&[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22], EcCurve::P384),
+  (&[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x23], EcCurve::P521),
+  (
+    &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a],
+    EcCurve::Secp256k1,
+  ),
+];
+
+/// detects the named curve of a DER encoded EC key (SPKI, PKCS#8 or SEC1)
+pub fn ec_curve(der: &[u8]) -> Option<EcCurve> {
+  EC_CURVE_OIDS
+    .iter()
+    .find(|(oid, _)| der.windows(oid.len()).any(|w| w == *oid))
+    .map(|(_, curve)| *curve)
+}
+
+/// fails when the curve of a DER encoded EC key doesn't match the one required by the token's `alg`
+pub fn check_ec_curve(alg: &str, der: &[u8]) -> JWTResult<()> {
+  check_curve(alg, ec_curve(der), "The secret provided")
+}
+
+/// fails when `key`, described like `The secret provided`, is on another curve than `alg` requires.
+/// Keys of unknown curves are left for the signature check to reject
+fn check_curve(alg: &str, curve: Option<EcCurve>, key: &str) -> JWTResult<()> {
+  let expected = match alg {
+    "ES256" => EcCurve::P256,
+    "ES384" => EcCurve::P384,
+    "ES512" => EcCurve::P521,
+    "ES256K" => EcCurve::Secp256k1,
+    _ => return Ok(()),
   };
+  match curve {
+    Some(curve) if curve != expected => Err(JWTError::Internal(format!(
+      "{key} is a {curve} key, but `alg` {alg} requires a {expected} key"
+    ))),
+    _ => Ok(()),
+  }
+}
 
-  DecodingKey::from_jwk(jwk).map_err(Error::into)
+/// returns the DER content of the first PEM block
+pub fn pem_to_der(pem: &[u8]) -> Option<Vec<u8>> {
+  let pem = from_utf8(pem).ok()?;
+  let body = pem
+    .lines()
+    .skip_while(|line| !line.starts_with("-----BEGIN"))
+    .skip(1)
+    .take_while(|line| !line.starts_with("-----END"))
+    .collect::<String>();
+  STANDARD
+    .decode(body.trim())
+    .ok()
+    .filter(|der| !der.is_empty())
 }
 
-fn parse_jwks(secret: &[u8]) -> Option<jwk::JwkSet> {
-  match serde_json::from_slice(secret) {
-    Ok(jwks) => Some(jwks),
-    Err(_) => None,
+fn decoding_keys_from_jwks(
+  jwks: jwk::JwkSet,
+  alg: &Algorithm,
+  header: &Header,
+) -> JWTResult<Vec<DecodingKey>> {
+  if let Some(jwk) = header.kid.as_ref().and_then(|kid| jwks.find(kid)) {
+    // the key is picked by `kid` alone, a key on another curve would only fail as a bad signature
+    if let jwk::AlgorithmParameters::EllipticCurve(ec) = &jwk.algorithm {
+      let curve = match ec.curve {
+        jwk::EllipticCurve::P256 => Some(EcCurve::P256),
+        jwk::EllipticCurve::P384 => Some(EcCurve::P384),
+        jwk::EllipticCurve::P521 => Some(EcCurve::P521),
+        jwk::EllipticCurve::Ed25519 => None,
+      };
+      let kid = header.kid.as_deref().unwrap_or_default();
+      check_curve(
+        &format!("{alg:?}"),
+        curve,
+        &format!("The JWK with 'kid' {kid:?}"),
+      )?;
+    }
+    return Ok(vec![DecodingKey::from_jwk(jwk)?]);
   }
+
+  let keys = jwks
+    .keys
+    .iter()
+    .filter(|jwk| is_jwk_compatible(jwk, alg))
+    .filter_map(|jwk| DecodingKey::from_jwk(jwk).ok())
+    .collect::<Vec<_>>();
+  if keys.is_empty() {
+    let kid = match &header.kid {
+      Some(kid) => format!(" and none has 'kid' {kid:?}"),
+      None => String::new(),
+    };
+    return Err(JWTError::Internal(format!(
+      "The JWKS has no key compatible with {alg:?}{kid}"
+    )));
+  }
+  Ok(keys)
+}
+
+/// signing key of the right type (and curve) whose `alg`, if set, matches
+fn is_jwk_compatible(jwk: &jwk::Jwk, alg: &Algorithm) -> bool {
+  use jwk::{AlgorithmParameters, EllipticCurve, PublicKeyUse};
+
+  if matches!(jwk.common.public_key_use, Some(PublicKeyUse::Encryption)) {
+    return false;
+  }
+  if let Some(key_alg) = jwk.common.key_algorithm {
+    if format!("{key_alg:?}") != format!("{alg:?}") {
+      return false;
+    }
+  }
+  match (&jwk.algorithm, alg) {
+    (AlgorithmParameters::OctetKey(_), Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) => {
+      true
+    }
+    (
+      AlgorithmParameters::RSA(_),
+      Algorithm::RS256
+      | Algorithm::RS384
+      | Algorithm::RS512
+      | Algorithm::PS256
+      | Algorithm::PS384
+      | Algorithm::PS512,
+    ) => true,
+    (AlgorithmParameters::EllipticCurve(ec), Algorithm::ES256) => ec.curve == EllipticCurve::P256,
+    (AlgorithmParameters::EllipticCurve(ec), Algorithm::ES384) => ec.curve == EllipticCurve::P384,
+    (AlgorithmParameters::OctetKeyPair(_), Algorithm::EdDSA) => true,
+    _ => false,
+  }
+}
+
+fn parse_jwks(secret: &[u8]) -> Option<jwk::JwkSet> {
+  serde_json::from_slice(secret).ok()
 }
 
 fn get_secret_file_type(secret_string: &str) -> SecretType {
-  if secret_string.ends_with(".pem") {
+  // certificate bundles are PEM, the public key is read from the leaf
+  if ["pem", "crt", "cer"]
+    .iter()
+    .any(|extension| has_file_extension(secret_string, extension))
+  {
     SecretType::Pem
-  } else if secret_string.ends_with(".json") {
+  } else if has_file_extension(secret_string, "json") {
+    SecretType::Jwks
+  } else {
+    SecretType::Der
+  }
+}
+
+/// type of a streamed secret by its content, as it has no file extension
+fn get_streamed_secret_type(content: &[u8]) -> SecretType {
+  if content.trim_ascii_start().starts_with(b"-----BEGIN") {
+    SecretType::Pem
+  } else if from_utf8(content).is_ok_and(is_jwks_text) {
     SecretType::Jwks
   } else {
     SecretType::Der
@@ -169,7 +771,7 @@ fn get_secret_file_type(secret_string: &str) -> SecretType {
 }
 
 fn map_external_error(ext_err: &Error) -> String {
-  return match ext_err.kind() {
+  match ext_err.kind() {
         ErrorKind::InvalidToken => {
           "The JWT provided is invalid".to_string()
         }
@@ -204,7 +806,7 @@ fn map_external_error(ext_err: &Error) -> String {
         }
         ErrorKind::InvalidAlgorithm => "The JWT provided has a different signing algorithm than the one you provided".to_string(),
         _ => format!("The JWT provided is invalid because {:?}", ext_err),
-      };
+      }
 }
 
 #[cfg(test)]
@@ -228,6 +830,36 @@ mod tests {
     std::fs::remove_file(file_name).unwrap();
   }
 
+  #[test]
+  #[cfg(not(target_os = "windows"))]
+  fn test_slurp_file_descriptor() {
+    use std::os::fd::AsRawFd;
+
+    let file = File::open("./test_data/test_rsa_public_key.pem").unwrap();
+    let name = format!("fd:{}", file.as_raw_fd());
+    let content = fs::read("./test_data/test_rsa_public_key.pem").unwrap();
+
+    assert_eq!(slurp_file(name.clone()).unwrap(), content);
+    // the descriptor is read once, later decodes get the same content
+    drop(file);
+    assert_eq!(slurp_file(name.clone()).unwrap(), content);
+    assert!(matches!(
+      get_secret_from_file_or_input(&Algorithm::RS256, &format!("@{name}")).1,
+      SecretType::Pem
+    ));
+
+    assert_eq!(
+      slurp_file("fd:three".into()).unwrap_err().to_string(),
+      "`three` is not a file descriptor"
+    );
+    assert!(!is_streamed("./fd:3"));
+
+    assert!(reads_stdin("@-"));
+    assert!(reads_stdin("jwks:@fd:0"));
+    assert!(!reads_stdin("@fd:3"));
+    assert!(!reads_stdin("-"));
+  }
+
   #[test]
   #[should_panic(expected = "The system cannot find the file specified.")]
   #[cfg(target_os = "windows")]
@@ -245,4 +877,208 @@ mod tests {
 
     slurp_file(file_name.to_string()).unwrap();
   }
+
+  #[test]
+  fn test_decode_hex() {
+    assert_eq!(decode_hex("00ff7F"), Some(vec![0x00, 0xff, 0x7f]));
+    assert_eq!(decode_hex(""), Some(vec![]));
+    assert_eq!(decode_hex("abc"), None);
+    assert_eq!(decode_hex("zz"), None);
+  }
+
+  #[test]
+  fn test_hexdump() {
+    assert_eq!(
+      hexdump(b"{\"alg\":\"HS256\"}\n\0"),
+      "00000000  7b 22 61 6c 67 22 3a 22  48 53 32 35 36 22 7d 0a  |{\"alg\":\"HS256\"}.|\n\
+       00000010  00                                                |.|"
+    );
+    assert_eq!(hexdump(b""), "(no bytes)");
+  }
+
+  #[test]
+  fn test_secret_file_path() {
+    assert_eq!(strip_leading_symbol("@./keys/key.pem"), "./keys/key.pem");
+    assert_eq!(
+      strip_leading_symbol("@\"./my keys/key.pem\""),
+      "./my keys/key.pem"
+    );
+    // prefixes of other secret types stay part of the path
+    assert_eq!(strip_leading_symbol("@b64:key.pem"), "b64:key.pem");
+
+    assert!(matches!(
+      get_secret_file_type("@./keys/KEY.PEM"),
+      SecretType::Pem
+    ));
+    assert!(matches!(
+      get_secret_file_type("@\"./jwks.json\""),
+      SecretType::Jwks
+    ));
+    assert!(matches!(
+      get_secret_file_type("@./key.der"),
+      SecretType::Der
+    ));
+  }
+
+  #[test]
+  #[cfg(target_os = "windows")]
+  fn test_secret_file_path_windows() {
+    let secret = "@C:\\keys\\key.pem";
+    assert_eq!(strip_leading_symbol(secret), "C:\\keys\\key.pem");
+    assert!(Path::new(&strip_leading_symbol(secret)).is_absolute());
+    assert!(matches!(get_secret_file_type(secret), SecretType::Pem));
+
+    let secret = "@\\\\server\\share\\jwks.JSON";
+    assert_eq!(strip_leading_symbol(secret), "\\\\server\\share\\jwks.JSON");
+    assert!(Path::new(&strip_leading_symbol(secret)).is_absolute());
+    assert!(matches!(get_secret_file_type(secret), SecretType::Jwks));
+  }
+
+  #[test]
+  fn test_key_requirements() {
+    assert_eq!(
+      key_requirements("RS256").unwrap(),
+      "RS256 needs an RSA public key (PEM/DER/JWKS) to verify; private key to sign"
+    );
+    assert_eq!(
+      key_requirements("HS512").unwrap(),
+      "HS512 needs the shared secret to verify and sign (plain text, 'b64:' base64, 'hex:' hex or JWKS)"
+    );
+    assert!(key_requirements("ES256K").unwrap().contains("secp256k1"));
+    assert_eq!(key_requirements("XYZ"), None);
+
+    assert_eq!(
+      verify_prompt("RS256").unwrap(),
+      "Provide an RSA public key to verify this RS256 token"
+    );
+    assert_eq!(
+      verify_prompt("HS256").unwrap(),
+      "Provide the shared secret to verify this HS256 token"
+    );
+    assert_eq!(verify_prompt("none"), None);
+    assert_eq!(verify_prompt("XYZ"), None);
+  }
+
+  #[test]
+  fn test_is_jwks_text() {
+    assert!(is_jwks_text(r#"{"keys":[]}"#));
+    assert!(is_jwks_text(r#" {"keys":[{"kty":"oct","k":"c2VjcmV0"}]} "#));
+    assert!(!is_jwks_text(r#"{"kty":"oct","k":"c2VjcmV0"}"#));
+    assert!(!is_jwks_text(r#"{"keys":"nope"}"#));
+    assert!(!is_jwks_text("plain secret"));
+  }
+
+  #[test]
+  fn test_web_decoder_url() {
+    assert_eq!(
+      web_decoder_url("https://jwt.example/#token={token}&v=1", "a.b.c"),
+      "https://jwt.example/#token=a.b.c&v=1"
+    );
+    assert_eq!(
+      web_decoder_url("https://jwt.example/?token=", "a.b.c"),
+      "https://jwt.example/?token=a.b.c"
+    );
+  }
+
+  #[test]
+  fn test_curl_command() {
+    assert_eq!(
+      curl_command("https://api.example/me?a=1&b=2", "a.b.c"),
+      r#"curl -H "Authorization: Bearer a.b.c" 'https://api.example/me?a=1&b=2'"#
+    );
+    assert_eq!(
+      curl_command("https://api.example/it's", "a.b.c"),
+      r#"curl -H "Authorization: Bearer a.b.c" 'https://api.example/it'\''s'"#
+    );
+  }
+
+  #[test]
+  fn test_osc52_sequence() {
+    assert_eq!(osc52_sequence("a.b.c", false), "\x1b]52;c;YS5iLmM=\x07");
+    assert_eq!(
+      osc52_sequence("a.b.c", true),
+      "\x1bPtmux;\x1b\x1b]52;c;YS5iLmM=\x07\x1b\\"
+    );
+  }
+
+  #[test]
+  fn test_human_size() {
+    assert_eq!(human_size(512), "512 B");
+    assert_eq!(human_size(64 * 1024), "64.0 KB");
+    assert_eq!(human_size(3 * 1024 * 1024 / 2), "1.5 MB");
+  }
+
+  #[test]
+  fn test_token_fingerprint() {
+    let fingerprint = token_fingerprint("eyJhbGciOiJIUzI1NiJ9.e30.c2ln");
+    assert_eq!(fingerprint.len(), 8);
+    assert!(fingerprint.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(
+      fingerprint,
+      token_fingerprint("eyJhbGciOiJIUzI1NiJ9.e30.c2ln")
+    );
+    assert_ne!(
+      fingerprint,
+      token_fingerprint("eyJhbGciOiJIUzI1NiJ9.e30.c2lm")
+    );
+  }
+
+  #[test]
+  fn test_ec_curve() {
+    let p384 = pem_to_der(&slurp_file("./test_data/test_ecdsa_public_key.pem".into()).unwrap());
+    assert_eq!(ec_curve(&p384.unwrap()), Some(EcCurve::P384));
+
+    let secp256k1 =
+      pem_to_der(&slurp_file("./test_data/test_es256k_private_key.pem".into()).unwrap()).unwrap();
+    assert_eq!(ec_curve(&secp256k1), Some(EcCurve::Secp256k1));
+    assert!(check_ec_curve("ES256K", &secp256k1).is_ok());
+    assert_eq!(
+      check_ec_curve("ES384", &secp256k1),
+      Err(JWTError::Internal(
+        "The secret provided is a secp256k1 key, but `alg` ES384 requires a P-384 key".into()
+      ))
+    );
+
+    let p256 =
+      pem_to_der(&slurp_file("./test_data/test_ecdsa_p256_public_key.pem".into()).unwrap())
+        .unwrap();
+    assert_eq!(ec_curve(&p256), Some(EcCurve::P256));
+    assert!(check_ec_curve("ES256", &p256).is_ok());
+    assert_eq!(
+      check_ec_curve("ES384", &p256),
+      Err(JWTError::Internal(
+        "The secret provided is a P-256 key, but `alg` ES384 requires a P-384 key".into()
+      ))
+    );
+    let p521 =
+      pem_to_der(&slurp_file("./test_data/test_ecdsa_p521_public_key.pem".into()).unwrap())
+        .unwrap();
+    assert_eq!(ec_curve(&p521), Some(EcCurve::P521));
+    assert!(check_ec_curve("ES512", &p521).is_ok());
+    assert_eq!(
+      check_ec_curve("ES512", &p256),
+      Err(JWTError::Internal(
+        "The secret provided is a P-256 key, but `alg` ES512 requires a P-521 key".into()
+      ))
+    );
+
+    assert_eq!(ec_curve(b"not a key"), None);
+    assert_eq!(pem_to_der(b"not a pem"), None);
+  }
+
+  #[test]
+  fn test_percent_decode() {
+    assert_eq!(
+      percent_decode("https%3A%2F%2Fexample.com%2Fcallback%3Fa%3D1"),
+      "https://example.com/callback?a=1"
+    );
+    assert_eq!(percent_decode("John Doe"), "John Doe");
+    // incomplete or invalid escapes are kept as-is
+    assert_eq!(percent_decode("100%"), "100%");
+    assert_eq!(percent_decode("%zz%4"), "%zz%4");
+    assert_eq!(percent_decode("%+1%-1"), "%+1%-1");
+    assert_eq!(percent_decode("%41"), "A");
+    // non UTF-8 results fall back to the original value
+    assert_eq!(percent_decode("%ff"), "%ff");
+  }
 }
@@ -0,0 +1,328 @@
+use std::fmt;
+
+use jsonwebtoken::errors::ErrorKind;
+
+use serde_json::Value;
+
+use super::{
+  advisories::{check_crit, check_weak_alg},
+  config::Config,
+  jwt_decoder::{decode, decode_segment, DecodeOptions, Decoded, Validity, DEFAULT_MAX_TOKEN_SIZE},
+  utils::{DecodeError, JWTError},
+};
+
+/// Reason a token failed `verify`. The `Display` output is kept stable so that scripts can match on it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyFailure {
+  MalformedToken,
+  MissingSecret,
+  InvalidKey,
+  InvalidSignature,
+  AlgorithmMismatch,
+  MissingExp,
+  Expired,
+  NotYetValid,
+  /// `alg: none`, rejected with `--strict`
+  Unsecured,
+  /// a problem with the `crit` header parameter, rejected with `--strict`
+  CriticalHeader(String),
+  /// a weak `alg` that wasn't allowed with `--allow-alg`, rejected with `--strict`
+  DisallowedAlg(String),
+  /// longer than the max token size
+  TokenTooLarge,
+  /// any other validation error reported by the decoder
+  Invalid,
+}
+
+impl fmt::Display for VerifyFailure {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let reason = match self {
+      VerifyFailure::MalformedToken => "malformed token",
+      VerifyFailure::MissingSecret => "missing secret",
+      VerifyFailure::InvalidKey => "invalid key",
+      VerifyFailure::InvalidSignature => "invalid signature",
+      VerifyFailure::AlgorithmMismatch => "algorithm mismatch",
+      VerifyFailure::MissingExp => "missing exp claim",
+      VerifyFailure::Expired => "token expired",
+      VerifyFailure::NotYetValid => "token not yet valid",
+      VerifyFailure::Unsecured => "unsecured token (alg: none)",
+      VerifyFailure::CriticalHeader(reason) => return write!(f, "critical header: {reason}"),
+      VerifyFailure::DisallowedAlg(alg) => return write!(f, "algorithm not allowed: {alg}"),
+      VerifyFailure::TokenTooLarge => "token too large",
+      VerifyFailure::Invalid => "invalid token",
+    };
+    write!(f, "{reason}")
+  }
+}
+
+impl VerifyFailure {
+  /// identifier of the reason for machine readable output, e.g. `expired`
+  pub fn code(&self) -> &'static str {
+    match self {
+      VerifyFailure::MalformedToken => "malformed_token",
+      VerifyFailure::MissingSecret => "missing_secret",
+      VerifyFailure::InvalidKey => "invalid_key",
+      VerifyFailure::InvalidSignature => "invalid_signature",
+      VerifyFailure::AlgorithmMismatch => "algorithm_mismatch",
+      VerifyFailure::MissingExp => "missing_exp",
+      VerifyFailure::Expired => "expired",
+      VerifyFailure::NotYetValid => "not_yet_valid",
+      VerifyFailure::Unsecured => "unsecured",
+      VerifyFailure::CriticalHeader(_) => "critical_header",
+      VerifyFailure::DisallowedAlg(_) => "disallowed_alg",
+      VerifyFailure::TokenTooLarge => "token_too_large",
+      VerifyFailure::Invalid => "invalid",
+    }
+  }
+}
+
+impl From<&JWTError> for VerifyFailure {
+  fn from(value: &JWTError) -> Self {
+    match value {
+      // secrets failing to load/parse surface as internal errors
+      JWTError::Internal(_) | JWTError::Decode(DecodeError::KeyParse(_)) => {
+        VerifyFailure::InvalidKey
+      }
+      JWTError::Decode(DecodeError::UnsupportedAlg(_)) => VerifyFailure::AlgorithmMismatch,
+      JWTError::Decode(DecodeError::TokenTooLarge(_)) => VerifyFailure::TokenTooLarge,
+      JWTError::Decode(_) => VerifyFailure::MalformedToken,
+      JWTError::External(err, _) => match err.kind() {
+        ErrorKind::InvalidToken
+        | ErrorKind::Base64(_)
+        | ErrorKind::Json(_)
+        | ErrorKind::Utf8(_) => VerifyFailure::MalformedToken,
+        ErrorKind::InvalidSignature => VerifyFailure::InvalidSignature,
+        ErrorKind::InvalidAlgorithm | ErrorKind::InvalidAlgorithmName => {
+          VerifyFailure::AlgorithmMismatch
+        }
+        ErrorKind::InvalidEcdsaKey
+        | ErrorKind::InvalidRsaKey(_)
+        | ErrorKind::RsaFailedSigning
+        | ErrorKind::InvalidKeyFormat => VerifyFailure::InvalidKey,
+        ErrorKind::ExpiredSignature => VerifyFailure::Expired,
+        ErrorKind::ImmatureSignature => VerifyFailure::NotYetValid,
+        _ => VerifyFailure::Invalid,
+      },
+    }
+  }
+}
+
+/// the header checks `--strict` turns from advisories into failures: an unsecured `alg: none`,
+/// `crit` parameters that aren't understood or malformed, and the weak algs of `config`. Tokens
+/// without a readable header are left for the decoder to report
+pub fn check_conformance(token: &str, config: &Config) -> Result<(), VerifyFailure> {
+  let Some(header) = decode_segment(token.trim(), 0) else {
+    return Ok(());
+  };
+  let alg = match serde_json::from_slice::<Value>(&header) {
+    Ok(header) => header.get("alg").and_then(Value::as_str).map(String::from),
+    Err(_) => return Ok(()),
+  };
+  if alg.as_deref() == Some("none") {
+    return Err(VerifyFailure::Unsecured);
+  }
+  if let Some(advisory) = check_crit(&header).into_iter().next() {
+    return Err(VerifyFailure::CriticalHeader(advisory.message));
+  }
+  if !check_weak_alg(&header, config).is_empty() {
+    return Err(VerifyFailure::DisallowedAlg(alg.unwrap_or_default()));
+  }
+  Ok(())
+}
+
+/// Validates the signature and the `exp`/`nbf` time claims of a token, allowing `leeway` seconds of
+/// clock skew relative to `now`. Tokens over [`DEFAULT_MAX_TOKEN_SIZE`] are rejected
+pub fn verify(token: &str, secret: &str, leeway: i64, now: i64) -> Result<(), VerifyFailure> {
+  verify_decoded(token, secret, leeway, now, DEFAULT_MAX_TOKEN_SIZE).map(|_| ())
+}
+
+/// like `verify`, but returns the decoded token so that its claims are only read once it passed,
+/// and rejects the tokens over `max_token_size` bytes
+pub fn verify_decoded(
+  token: &str,
+  secret: &str,
+  leeway: i64,
+  now: i64,
+  max_token_size: usize,
+) -> Result<Decoded, VerifyFailure> {
+  if secret.is_empty() {
+    return Err(VerifyFailure::MissingSecret);
+  }
+  // time claims are checked below with the given leeway instead of the decoder's
+  let decoded = decode(
+    token.trim(),
+    &DecodeOptions {
+      secret: secret.into(),
+      ignore_exp: true,
+      max_token_size: Some(max_token_size),
+      ..DecodeOptions::default()
+    },
+  )
+  .map_err(|e| VerifyFailure::from(&e))?;
+
+  if let Validity::Unverified(e) = &decoded.validity {
+    return Err(e.into());
+  }
+
+  match decoded.payload.timestamp("exp") {
+    None => return Err(VerifyFailure::MissingExp),
+    Some(exp) if exp + leeway < now => return Err(VerifyFailure::Expired),
+    _ => {}
+  }
+  if let Some(nbf) = decoded.payload.timestamp("nbf") {
+    if nbf - leeway > now {
+      return Err(VerifyFailure::NotYetValid);
+    }
+  }
+  Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+  use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+  use jsonwebtoken::{encode, EncodingKey, Header};
+  use serde_json::{json, Value};
+
+  use super::*;
+
+  const NOW: i64 = 1_700_000_000;
+
+  fn token(claims: Value) -> String {
+    encode(
+      &Header::default(),
+      &claims,
+      &EncodingKey::from_secret(b"secret"),
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn test_verify_valid_token() {
+    let token = token(json!({ "sub": "1", "exp": NOW + 60, "nbf": NOW - 60 }));
+    assert_eq!(verify(&token, "secret", 0, NOW), Ok(()));
+    // surrounding whitespace from shell input is ignored
+    assert_eq!(verify(&format!(" {token}\n"), "secret", 0, NOW), Ok(()));
+  }
+
+  #[test]
+  fn test_verify_signature_failures() {
+    let token = token(json!({ "exp": NOW + 60 }));
+    assert_eq!(
+      verify(&token, "wrong", 0, NOW),
+      Err(VerifyFailure::InvalidSignature)
+    );
+    assert_eq!(
+      verify(&token, "", 0, NOW),
+      Err(VerifyFailure::MissingSecret)
+    );
+    assert_eq!(
+      verify("not-a-token", "secret", 0, NOW),
+      Err(VerifyFailure::MalformedToken)
+    );
+    // {"alg":"none"}
+    assert_eq!(
+      verify("eyJhbGciOiJub25lIn0.e30.", "secret", 0, NOW),
+      Err(VerifyFailure::AlgorithmMismatch)
+    );
+  }
+
+  #[test]
+  fn test_verify_decoded() {
+    let token = token(json!({ "sub": "1", "exp": NOW + 60 }));
+    let decoded = verify_decoded(&token, "secret", 0, NOW, DEFAULT_MAX_TOKEN_SIZE).unwrap();
+    assert_eq!(decoded.payload.get_path("sub"), Ok(&json!("1")));
+    assert_eq!(
+      verify_decoded(&token, "wrong", 0, NOW, DEFAULT_MAX_TOKEN_SIZE).map(|_| ()),
+      Err(VerifyFailure::InvalidSignature)
+    );
+    assert_eq!(
+      verify_decoded(&token, "secret", 0, NOW, 32).map(|_| ()),
+      Err(VerifyFailure::TokenTooLarge)
+    );
+  }
+
+  #[test]
+  fn test_verify_time_failures() {
+    let expired = token(json!({ "exp": NOW - 100 }));
+    assert_eq!(
+      verify(&expired, "secret", 0, NOW),
+      Err(VerifyFailure::Expired)
+    );
+    assert_eq!(verify(&expired, "secret", 120, NOW), Ok(()));
+
+    let immature = token(json!({ "exp": NOW + 600, "nbf": NOW + 100 }));
+    assert_eq!(
+      verify(&immature, "secret", 0, NOW),
+      Err(VerifyFailure::NotYetValid)
+    );
+
+    let no_exp = token(json!({ "sub": "1" }));
+    assert_eq!(
+      verify(&no_exp, "secret", 0, NOW),
+      Err(VerifyFailure::MissingExp)
+    );
+  }
+
+  #[test]
+  fn test_check_conformance() {
+    let config = Config::default();
+    let header = |json: &str| format!("{}.e30.c2ln", URL_SAFE_NO_PAD.encode(json));
+    assert_eq!(check_conformance(&token(json!({})), &config), Ok(()));
+    assert_eq!(check_conformance("not-a-token", &config), Ok(()));
+
+    let unsecured = check_conformance("eyJhbGciOiJub25lIn0.e30.", &config).unwrap_err();
+    assert_eq!(unsecured, VerifyFailure::Unsecured);
+    assert_eq!(unsecured.to_string(), "unsecured token (alg: none)");
+
+    let crit = check_conformance(
+      &header(r#"{"alg":"HS256","crit":["ext"],"ext":1}"#),
+      &config,
+    )
+    .unwrap_err();
+    assert_eq!(crit.code(), "critical_header");
+    assert_eq!(
+      crit.to_string(),
+      "critical header: Critical `ext` is not understood, a compliant verifier rejects the token"
+    );
+    // `b64` is understood, but must be critical
+    let b64 = |header: &str| format!("{}.{{}}.c2ln", URL_SAFE_NO_PAD.encode(header));
+    assert_eq!(
+      check_conformance(
+        &b64(r#"{"alg":"HS256","crit":["b64"],"b64":false}"#),
+        &config
+      ),
+      Ok(())
+    );
+    assert_eq!(
+      check_conformance(&b64(r#"{"alg":"HS256","b64":false}"#), &config)
+        .unwrap_err()
+        .code(),
+      "critical_header"
+    );
+
+    let weak = check_conformance(&header(r#"{"alg":"HS1"}"#), &config).unwrap_err();
+    assert_eq!(weak, VerifyFailure::DisallowedAlg("HS1".into()));
+    assert_eq!(weak.to_string(), "algorithm not allowed: HS1");
+    let mut allowed = Config::default();
+    allowed.weak_algs.remove("HS1");
+    assert_eq!(
+      check_conformance(&header(r#"{"alg":"HS1"}"#), &allowed),
+      Ok(())
+    );
+  }
+
+  #[test]
+  fn test_verify_failure_reasons_are_stable() {
+    assert_eq!(
+      VerifyFailure::InvalidSignature.to_string(),
+      "invalid signature"
+    );
+    assert_eq!(VerifyFailure::Expired.to_string(), "token expired");
+    assert_eq!(
+      VerifyFailure::NotYetValid.to_string(),
+      "token not yet valid"
+    );
+    assert_eq!(VerifyFailure::Expired.code(), "expired");
+    assert_eq!(VerifyFailure::MalformedToken.code(), "malformed_token");
+  }
+}
@@ -1,6 +1,9 @@
 //  adapted from tui-rs/examples/crossterm_demo.rs
 use std::{
-  sync::mpsc,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc,
+  },
   thread,
   time::{Duration, Instant},
 };
@@ -41,6 +44,8 @@ pub struct Events {
   rx: mpsc::Receiver<Event<KeyEvent, MouseEvent>>,
   // Need to be kept around to prevent disposing the sender side.
   _tx: mpsc::Sender<Event<KeyEvent, MouseEvent>>,
+  /// input isn't read while another program like an editor has the terminal
+  paused: Arc<AtomicBool>,
 }
 
 impl Events {
@@ -59,14 +64,21 @@ impl Events {
     let tick_rate = config.tick_rate;
 
     let event_tx = tx.clone();
+    let paused = Arc::new(AtomicBool::new(false));
+    let input_paused = paused.clone();
     thread::spawn(move || {
       let mut last_tick = Instant::now();
       loop {
+        if input_paused.load(Ordering::Relaxed) {
+          thread::sleep(Duration::from_millis(10));
+          continue;
+        }
         let timeout = tick_rate
           .checked_sub(last_tick.elapsed())
           .unwrap_or_else(|| Duration::from_secs(0));
         // poll for tick rate duration, if no event, sent tick event.
-        if event::poll(timeout).unwrap() {
+        // Input arriving once paused is left for the program that has the terminal
+        if event::poll(timeout).unwrap() && !input_paused.load(Ordering::Relaxed) {
           let e = event::read().unwrap();
           match e {
             CEvent::Key(key_event) => handle_key_event(&event_tx, key_event),
@@ -83,7 +95,20 @@ impl Events {
       }
     });
 
-    Events { rx, _tx: tx }
+    Events {
+      rx,
+      _tx: tx,
+      paused,
+    }
+  }
+
+  /// stops reading input, until `resume`
+  pub fn pause(&self) {
+    self.paused.store(true, Ordering::Relaxed);
+  }
+
+  pub fn resume(&self) {
+    self.paused.store(false, Ordering::Relaxed);
   }
 
   /// Attempts to read an event.
@@ -1,5 +1,5 @@
 // from https://github.com/Rigellute/spotify-tui
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 use crossterm::event::{self, KeyCode};
 
@@ -110,6 +110,72 @@ impl fmt::Display for Key {
   }
 }
 
+/// Reads a key as it's shown in the help, like `<c>`, `<Ctrl+d>`, `<Enter>` or `<F5>`. The angle
+/// brackets are optional and the names of keys and modifiers aren't case sensitive
+impl FromStr for Key {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let error = || format!("`{s}` is not a key like `c`, `Ctrl+d`, `Enter` or `F5`");
+    let name = s
+      .strip_prefix('<')
+      .and_then(|s| s.strip_suffix('>'))
+      .unwrap_or(s);
+    let name = name.strip_suffix(" Arrow Key").unwrap_or(name);
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+      return Ok(Key::Char(c));
+    }
+    if let Some((modifier, key)) = name.split_once('+').filter(|(_, key)| !key.is_empty()) {
+      let mut chars = key.chars();
+      let c = match (chars.next(), chars.next()) {
+        (Some(c), None) => c,
+        _ if key.eq_ignore_ascii_case("space") => ' ',
+        _ if modifier.eq_ignore_ascii_case("ctrl") => {
+          return match key.to_ascii_lowercase().as_str() {
+            "left" => Ok(Key::CtrlK(KeyCode::Left)),
+            "right" => Ok(Key::CtrlK(KeyCode::Right)),
+            "delete" => Ok(Key::CtrlK(KeyCode::Delete)),
+            _ => Err(error()),
+          };
+        }
+        _ => return Err(error()),
+      };
+      return match modifier.to_ascii_lowercase().as_str() {
+        "ctrl" => Ok(Key::Ctrl(c)),
+        "alt" => Ok(Key::Alt(c)),
+        "meta" => Ok(Key::Meta(c)),
+        _ => Err(error()),
+      };
+    }
+    let key = match name.to_ascii_lowercase().as_str() {
+      "enter" => Key::Enter,
+      "tab" => Key::Tab,
+      "backspace" => Key::Backspace,
+      "esc" => Key::Esc,
+      "left" => Key::Left,
+      "right" => Key::Right,
+      "up" => Key::Up,
+      "down" => Key::Down,
+      "ins" => Key::Ins,
+      "delete" => Key::Delete,
+      "home" => Key::Home,
+      "end" => Key::End,
+      "pageup" => Key::PageUp,
+      "pagedown" => Key::PageDown,
+      "space" => Key::Char(' '),
+      function => match function
+        .strip_prefix('f')
+        .and_then(|n| n.parse::<u8>().ok())
+      {
+        Some(n) if n <= 12 => Key::from_f(n),
+        _ => return Err(error()),
+      },
+    };
+    Ok(key)
+  }
+}
+
 impl From<event::KeyEvent> for Key {
   fn from(key_event: event::KeyEvent) -> Self {
     match key_event {
@@ -225,6 +291,35 @@ mod tests {
     assert_eq!(format!("{}", Key::Enter), "<Enter>");
     assert_eq!(format!("{}", Key::F10), "<F10>");
   }
+  #[test]
+  fn test_key_from_str() {
+    assert_eq!("c".parse(), Ok(Key::Char('c')));
+    assert_eq!("<C>".parse(), Ok(Key::Char('C')));
+    assert_eq!("<".parse(), Ok(Key::Char('<')));
+    assert_eq!("ctrl+d".parse(), Ok(Key::Ctrl('d')));
+    assert_eq!("<Alt+Space>".parse(), Ok(Key::Alt(' ')));
+    assert_eq!("Ctrl+Left".parse(), Ok(Key::CtrlK(KeyCode::Left)));
+    assert_eq!("<Left Arrow Key>".parse(), Ok(Key::Left));
+    assert_eq!("PageDown".parse(), Ok(Key::PageDown));
+    assert_eq!("<F10>".parse(), Ok(Key::F10));
+    // the help shows each key the way it's read back
+    for key in [
+      Key::Enter,
+      Key::Ctrl('w'),
+      Key::Meta('x'),
+      Key::Char(' '),
+      Key::Down,
+    ] {
+      assert_eq!(key.to_string().parse(), Ok(key));
+    }
+    assert_eq!(
+      "F13".parse::<Key>(),
+      Err("`F13` is not a key like `c`, `Ctrl+d`, `Enter` or `F5`".into())
+    );
+    assert!("Shift+x".parse::<Key>().is_err());
+    assert!("".parse::<Key>().is_err());
+  }
+
   #[test]
   fn test_key_from_event() {
     assert_eq!(Key::from(event::KeyEvent::from(KeyCode::Esc)), Key::Esc);
@@ -0,0 +1,17 @@
+//! Decoding/encoding core and terminal UI of JWT UI.
+//!
+//! The [`decode`] function can be used to decode and verify tokens from other programs without the TUI.
+//! The registered claims of the payload can be read with their types with
+//! [`Payload::standard_claims`], the header is a [`TokenHeader`]. A token that can't be decoded fails
+//! with [`JWTError::Decode`], whose [`DecodeError`] can be matched on.
+#![warn(rust_2018_idioms)]
+pub mod app;
+pub mod event;
+pub mod handlers;
+pub mod ui;
+
+pub use app::{
+  claims::{Audience, StandardClaims},
+  jwt_decoder::{decode, DecodeOptions, Decoded, Payload, TokenHeader, Validity},
+  utils::{DecodeError, JWTError, Segment},
+};
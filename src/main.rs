@@ -1,41 +1,77 @@
 #![warn(rust_2018_idioms)]
-mod app;
 mod banner;
-mod event;
-mod handlers;
-mod ui;
 
 use std::{
+  collections::{BTreeMap, BTreeSet},
   error::Error,
-  io::{self, stdout, Stdout, Write},
-  panic::{self, PanicInfo},
+  fs,
+  io::{self, stdout, IsTerminal, Read, Stdout, Write},
+  panic::{self, PanicHookInfo},
+  path::PathBuf,
+  process,
+  str::FromStr,
 };
 
-use app::{jwt_decoder::print_decoded_token, App};
 use banner::BANNER;
-use clap::Parser;
+use chrono::{DateTime, Utc};
+use clap::{parser::ValueSource, Args, CommandFactory, FromArgMatches, Parser, Subcommand};
 use crossterm::{
   event::DisableMouseCapture,
   execute,
-  terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+  terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+  },
+};
+use jsonwebtoken::Algorithm;
+use jwt_ui::{
+  app::{
+    assertions::{check_assertions, Assertion},
+    bench::bench,
+    config::{config_dir, AudienceMode, Config, DateFormat, Severity, DEFAULT_MAX_EXP_IN_FUTURE},
+    csv_export::claims_to_csv,
+    debug_file::DebugFile,
+    external_editor::{editor_command, ExternalEdit},
+    jwt_decoder::{
+      decode_header_only, decode_jwt_token, extract_token, parse_claim_path, print_claim_at_path,
+      print_claims, print_decoded_token, print_error_json, print_raw_header, print_raw_payload,
+      DEFAULT_ARRAY_PREVIEW, DEFAULT_MAX_TOKEN_SIZE,
+    },
+    jwt_encoder::generate_token,
+    key_binding::{load_key_bindings, set_key_bindings, KEYBINDINGS_FILE},
+    logging::init_logging,
+    profiles::{load_profiles, select_profile, use_profile, Profile, Profiles, PROFILES_FILE},
+    settings::{load_settings, Setting, SETTINGS_FILE},
+    state::NavigationState,
+    summary::parse_jti_denylist,
+    utils::{read_clipboard, reads_stdin},
+    verify::{check_conformance, verify_decoded, VerifyFailure},
+    window_title::{supports_window_title, window_title, POP_TITLE, PUSH_TITLE},
+    App,
+  },
+  event::{self, Key},
+  handlers, ui,
 };
-use event::Key;
 use ratatui::{
   backend::{Backend, CrosstermBackend},
   Terminal,
 };
 
-use crate::app::jwt_decoder::decode_jwt_token;
+/// `--help` heading of the thresholds the token's lifetime is checked against, warnings are
+/// printed under `warnings` with `--output json`
+const HYGIENE: &str = "Token hygiene (warnings, not failures)";
 
 /// JWT UI
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None, before_help = BANNER)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Cli {
-  /// JWT token to decode [mandatory for stdout mode, optional for TUI mode].
+  #[command(subcommand)]
+  pub command: Option<Command>,
+  /// JWT token to decode [mandatory for stdout mode, optional for TUI mode]. Can also be a header line like `Authorization: Bearer <token>`.
   #[clap(index = 1)]
   #[clap(value_parser)]
   pub token: Option<String>,
-  /// Secret for validating the JWT. Can be text, file path (beginning with @) or base64 encoded string (beginning with b64:).
+  /// Secret for validating the JWT. Can be text, file path (beginning with @), base64 encoded string (beginning with b64:) or hex encoded string (beginning with hex:).
   #[arg(short = 'S', long, value_parser, default_value = "")]
   pub secret: String,
   /// Print to STDOUT instead of starting the CLI in TUI mode.
@@ -44,47 +80,849 @@ pub struct Cli {
   /// Do not validate the signature of the JWT when printing to STDOUT.
   #[arg(short, long, value_parser, default_value_t = false)]
   pub no_verify: bool,
+  /// Decode and print to STDOUT without attempting to verify the signature, noting on STDERR that the token wasn't verified. Makes skipping verification explicit, unlike leaving out the secret.
+  #[arg(
+    long,
+    value_parser,
+    default_value_t = false,
+    conflicts_with_all = ["secret", "require_signature"]
+  )]
+  pub insecure_no_verify: bool,
   /// Print to STDOUT as JSON.
   #[arg(short, long, value_parser, default_value_t = false)]
   pub json: bool,
+  /// Format of the output on STDOUT. With `json` errors are printed as JSON too, like `{"error": "expired", "detail": "..."}`, and exit with 1.
+  #[arg(long, value_name = "text|json", value_parser = str::parse::<OutputFormat>, default_value = "text", conflicts_with_all = ["raw_payload", "header"])]
+  pub output: OutputFormat,
+  /// Print only the payload as JSON to STDOUT, e.g. for `jq`. Fails closed: a token that can't be decoded or verified prints nothing and exits with 1.
+  #[arg(long, value_parser, default_value_t = false, conflicts_with = "json")]
+  pub raw_payload: bool,
+  /// Print only the header as JSON to STDOUT, e.g. to look up the key for its `kid` with `--no-verify`. Fails closed like `--raw-payload`.
+  #[arg(long, value_parser, default_value_t = false, conflicts_with_all = ["json", "raw_payload"])]
+  pub header: bool,
+  /// Print only the header as JSON to STDOUT without decoding the rest, so that tokens whose payload can't be decoded like JWEs still show their `alg` and `kid`. Nothing is verified.
+  #[arg(long, value_parser, default_value_t = false, conflicts_with_all = ["json", "raw_payload", "header"])]
+  pub header_only: bool,
+  /// Print only these comma separated claims as a JSON object to STDOUT, e.g. `sub,aud,exp`, keeping their types. Absent claims are left out. Fails closed like `--raw-payload`.
+  #[arg(long, value_name = "CLAIMS", value_parser = parse_claim_name, value_delimiter = ',', conflicts_with_all = ["json", "raw_payload", "header", "header_only"])]
+  pub claims: Vec<String>,
+  /// Print claims of `--claims` that the token doesn't have as `null` instead of leaving them out.
+  #[arg(long, value_parser, default_value_t = false, requires = "claims")]
+  pub null_missing: bool,
+  /// Print only the payload value at this path to STDOUT as JSON, e.g. `realm_access.roles[0]`. Keys with dots are quoted in brackets like `["a.b"]`. A missing path is an error. Fails closed like `--raw-payload`.
+  #[arg(long, value_name = "PATH", value_parser = parse_path, conflicts_with_all = ["json", "raw_payload", "header", "header_only", "claims"])]
+  pub path: Option<String>,
+  /// Print a string found at `--path` without JSON quotes.
+  #[arg(long, value_parser, default_value_t = false, requires = "path")]
+  pub raw: bool,
   /// Set the tick rate (milliseconds): the lower the number the higher the FPS. Must be less than 1000.
-  #[arg(short, long, value_parser, default_value_t = 250)]
+  #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..1000), default_value_t = 250)]
   pub tick_rate: u64,
+  /// Warn when the token lifetime (`exp - iat`) is shorter than this many seconds.
+  #[arg(long, value_parser, default_value_t = 60, help_heading = HYGIENE)]
+  pub min_lifetime: i64,
+  /// Warn when the token lifetime (`exp - iat`) is longer than this many seconds.
+  #[arg(long, value_parser, default_value_t = 86400, help_heading = HYGIENE)]
+  pub max_lifetime: i64,
+  /// Warn when `exp` is more than this many seconds in the future, a year by default. Such tokens are as good as never expiring.
+  #[arg(long, value_name = "SECONDS", value_parser, default_value_t = DEFAULT_MAX_EXP_IN_FUTURE, help_heading = HYGIENE)]
+  pub max_exp_in_future: i64,
+  /// Allowed clock skew (seconds) when checking time claims like `iat`.
+  #[arg(long, value_parser, default_value_t = 60)]
+  pub leeway: i64,
+  /// Reject token segments with base64 padding or the standard alphabet instead of normalizing them, for conformance testing. When printing to STDOUT, also exit with 1 on `alg: none`, `crit` header params that aren't understood and weak algs not allowed with `--allow-alg`.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub strict: bool,
+  /// Fail closed: treat tokens without a verified signature as invalid and exit with 1 when printing to STDOUT, also with `--no-verify`.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub require_signature: bool,
+  /// Check time claims against this instant instead of the system clock, as a unix timestamp or RFC 3339 date, for reproducible validation.
+  #[arg(long, value_name = "TIME", value_parser = parse_now)]
+  pub now: Option<i64>,
+  /// Audience expected in the `aud` claim, a string or an array. Can be repeated, see `--audience-mode`.
+  #[arg(long = "audience", value_name = "AUD", value_parser)]
+  pub audiences: Vec<String>,
+  /// Whether `aud` must hold `any` or `all` of the expected audiences.
+  #[arg(long, value_name = "any|all", value_parser = str::parse::<AudienceMode>, default_value = "any")]
+  pub audience_mode: AudienceMode,
+  /// Whether a missing audience makes the token invalid and exits with 1 when printing to STDOUT, `error`, or is only a warning, `warning`.
+  #[arg(long, value_name = "error|warning", value_parser = str::parse::<Severity>, default_value = "error")]
+  pub audience_severity: Severity,
+  /// Issuer expected in the `iss` claim.
+  #[arg(long, value_name = "ISS", value_parser)]
+  pub issuer: Option<String>,
+  /// Whether another issuer makes the token invalid and exits with 1 when printing to STDOUT, `error`, or is only a warning, `warning`.
+  #[arg(long, value_name = "error|warning", value_parser = str::parse::<Severity>, default_value = "error")]
+  pub issuer_severity: Severity,
+  /// File with the `jti` of tokens seen before, one per line. A token with one of them is a replay and invalid, exiting with 1 when printing to STDOUT, and a token without `jti` is warned about.
+  #[arg(long, value_name = "FILE", value_parser = read_jti_denylist)]
+  pub jti_denylist: Option<BTreeSet<String>>,
+  /// Profile of the profiles file in the config dir to use, bundling a secret, the expected issuer and audiences, allowed algs and the leeway. The profile `default` is used when not given. Options given on the command line take precedence.
+  #[arg(long, value_name = "NAME", value_parser)]
+  pub profile: Option<String>,
+  /// Show the claim as a UTC date like `exp`, `iat` and `nbf` when dates are shown in UTC, e.g. `auth_time`. Can be repeated.
+  #[arg(long = "time-claim", value_name = "CLAIM", value_parser)]
+  pub time_claims: Vec<String>,
+  /// Format of the dates shown in UTC: `iso8601` like `2023-11-14T22:13:20+00:00`, `human` like `2023-11-14 22:13:20 UTC` or `rfc2822` like `Tue, 14 Nov 2023 22:13:20 +0000`.
+  #[arg(long, value_name = "iso8601|human|rfc2822", value_parser = str::parse::<DateFormat>, default_value = "human")]
+  pub date_format: DateFormat,
+  /// Show `LABEL` for the claim in the payload, like `Tenant ID (x-tn)` for `x-tn=Tenant ID`. Display only, the JSON and copies keep the claim's name. Can be repeated.
+  #[arg(long, value_name = "CLAIM=LABEL", value_parser = parse_claim_label)]
+  pub claim_label: Vec<(String, String)>,
+  /// Verify with the public key of an expired certificate given as the secret, instead of failing.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub allow_expired_cert: bool,
+  /// Don't warn about tokens without an `exp` claim, for systems that legitimately issue tokens that never expire.
+  #[arg(long, value_parser, default_value_t = false, help_heading = HYGIENE)]
+  pub allow_no_exp: bool,
+  /// High-contrast colors, with `✓`/`✗`/`!` symbols next to the colors of the verdicts and warnings. Works with the light and dark themes, also a setting.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub high_contrast: bool,
+  /// Enable vim keys in addition to `h`/`j`/`k`/`l`: `gg`/`G` to scroll to the top/bottom.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub vim_keys: bool,
+  /// Lines or rows to scroll with the arrow keys and the mouse wheel.
+  #[arg(long, value_parser = clap::value_parser!(u16).range(1..), default_value_t = 1)]
+  pub scroll_step: u16,
+  /// Lines or rows to scroll with page up/down.
+  #[arg(long, value_parser = clap::value_parser!(u16).range(1..), default_value_t = 10)]
+  pub page_step: u16,
+  /// Warn when the HMAC secret for `ALG` is shorter than `BYTES`, e.g. `HS256=16`. Can be repeated [default: HS256=32, HS384=48, HS512=64]
+  #[arg(long, value_name = "ALG=BYTES", value_parser = parse_min_secret_length)]
+  pub min_secret_length: Vec<(String, usize)>,
+  /// Key tried when no secret is given, as `LABEL=SECRET` with `SECRET` like `--secret`, e.g. `prod=@prod.pem`. Can be repeated, the keys are tried in order and the label of the one that verified is reported. At most 16 are tried.
+  #[arg(long, value_name = "LABEL=SECRET", value_parser = parse_key)]
+  pub key: Vec<(String, String)>,
+  /// Warn in the header when the token uses `ALG`, with `REASON`. Can be repeated [default: none, RS1, HS1]
+  #[arg(long, value_name = "ALG=REASON", value_parser = parse_weak_alg)]
+  pub weak_alg: Vec<(String, String)>,
+  /// Don't warn about `ALG` even if it's weak by default, e.g. `none`. Can be repeated.
+  #[arg(long, value_name = "ALG", value_parser)]
+  pub allow_alg: Vec<String>,
+  /// URL of a web decoder to open the token in with `<O>` after confirming, `{token}` is replaced by the token or it's appended. The secret is never sent. Disabled when not set.
+  #[arg(long, value_name = "URL", value_parser)]
+  pub web_decoder_url: Option<String>,
+  /// URL of the `curl -H "Authorization: Bearer <token>" <URL>` command to copy from the copy menu, to try the token against an API. Disabled when not set.
+  #[arg(long, value_name = "URL", value_parser)]
+  pub curl_url: Option<String>,
+  /// Load the token from the system clipboard on start. Starts with an empty decoder when it holds no JWT.
+  #[arg(long, value_parser, default_value_t = false, conflicts_with = "token")]
+  pub from_clipboard: bool,
+  /// Elements of an array shown in the payload JSON before the rest is cut, expand them with `<a>`. 0 shows all.
+  #[arg(long, value_name = "N", value_parser, default_value_t = DEFAULT_ARRAY_PREVIEW)]
+  pub array_preview: usize,
+  /// Start the decoder in compact mode, with the signature block hidden. Toggle it with `<z>`.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub compact: bool,
+  /// Don't start editing the token when launched without one, start in the decoder's normal mode instead.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub no_edit_on_start: bool,
+  /// Restore the theme, the last active tab and block and the recent key files on start and save them on quit. Token and secret are never saved, only the paths of key files.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub restore_state: bool,
+  /// Append a debug log of the decodes, key file reads, clipboard access, notifications and errors to `PATH`, to troubleshoot the tool. The level is read from `RUST_LOG`, like `info` or `trace` [default: debug]. Tokens and secrets are never logged, only their fingerprints and lengths.
+  #[arg(long, value_name = "PATH", value_parser)]
+  pub log_file: Option<PathBuf>,
+  /// Show the claim of the token in the terminal window title, e.g. `sub` or `email`, to tell sessions apart. The title is restored on exit, terminals that don't support it are left alone.
+  #[arg(long, value_name = "CLAIM", value_parser)]
+  pub title_claim: Option<String>,
+  /// Reject tokens longer than this many bytes instead of decoding them, raise it for legitimately large tokens.
+  #[arg(long, value_name = "BYTES", value_parser, default_value_t = DEFAULT_MAX_TOKEN_SIZE)]
+  pub max_token_size: usize,
+  /// Ask for confirmation before quitting with `q` while a secret is entered, as it's never saved. `<ctrl+c>` always quits.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub confirm_quit: bool,
+  /// Triage many tokens: the token field starts ready for a paste, each token pasted replaces the last one once it was inspected and its verdict is notified and logged. The token block counts the inspected, expired and invalid tokens.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub batch: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+  /// Validate the signature and time claims of a JWT. Prints nothing on success, a single-line reason and exits with 1 on failure.
+  Verify(VerifyArgs),
+  /// Start the UI with the token and secret from a debug file with `token:` and `secret:` lines, so that the secret stays out of the shell history.
+  Open(OpenArgs),
+  /// Print selected claims of many tokens, one per line, as CSV. Signatures are not verified.
+  Csv(CsvArgs),
+  /// Sign a JSON object of claims with `--alg` and `--secret` and print the token, to make test tokens and fixtures. The claims are signed sorted by name, like in the encoder.
+  Gen(GenArgs),
+  /// Verify every token of a file with one key and print the verified and failed counts and the throughput, to check a key setup before deploying. Exits with 1 when a token failed.
+  #[command(hide = true)]
+  Bench(BenchArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+  /// JWT token to verify. Can also be a header line like `Authorization: Bearer <token>`.
+  #[clap(value_parser)]
+  pub token: String,
+  /// Secret for validating the JWT. Can be text, file path (beginning with @), base64 encoded string (beginning with b64:) or hex encoded string (beginning with hex:).
+  #[arg(short = 'S', long, value_parser, default_value = "")]
+  pub secret: String,
+  /// Allowed clock skew (seconds) when checking the `exp` and `nbf` claims.
+  #[arg(long, value_parser, default_value_t = 60)]
+  pub leeway: i64,
+  /// Check time claims against this instant instead of the system clock, as a unix timestamp or RFC 3339 date.
+  #[arg(long, value_name = "TIME", value_parser = parse_now)]
+  pub now: Option<i64>,
+  /// Also fail on `alg: none`, `crit` header params that aren't understood and the default weak algs.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub strict: bool,
+  /// Print the payload value at this path to STDOUT once the token passed, e.g. `sub`. Nothing is printed for a token that fails, its reason goes to STDERR instead.
+  #[arg(long, value_name = "PATH", value_parser = parse_path)]
+  pub path: Option<String>,
+  /// Print a string found at `--path` without JSON quotes.
+  #[arg(long, value_parser, default_value_t = false, requires = "path")]
+  pub raw: bool,
+  /// Also fail when the claim assertion doesn't hold, like `sub exists`, `roles contains admin` or `exp > now+3600`. The operators are `exists`, `equals`, `contains`, `>` and `<`. Can be repeated.
+  #[arg(long = "assert", value_name = "EXPR", value_parser = str::parse::<Assertion>)]
+  pub assertions: Vec<Assertion>,
+  /// Reject tokens longer than this many bytes instead of decoding them, like the UI does.
+  #[arg(long, value_name = "BYTES", value_parser, default_value_t = DEFAULT_MAX_TOKEN_SIZE)]
+  pub max_token_size: usize,
+}
+
+#[derive(Args, Debug)]
+pub struct OpenArgs {
+  /// Debug file to load, e.g. `bug.jwt`. A secret file (beginning with @) is resolved relative to it.
+  #[clap(value_parser)]
+  pub file: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct CsvArgs {
+  /// File with one token per line, read from STDIN when omitted.
+  #[clap(value_parser)]
+  pub file: Option<PathBuf>,
+  /// Comma separated claims to print as columns, e.g. `sub,email,exp`. An `error` column is added for tokens that can't be decoded.
+  #[arg(short, long, value_parser, value_delimiter = ',', required = true)]
+  pub columns: Vec<String>,
+  /// Print `exp`, `iat` and `nbf` as UTC dates instead of timestamps.
+  #[arg(short, long, value_parser, default_value_t = false)]
+  pub readable_dates: bool,
+  /// Reject tokens longer than this many bytes instead of decoding them, like the UI does.
+  #[arg(long, value_name = "BYTES", value_parser, default_value_t = DEFAULT_MAX_TOKEN_SIZE)]
+  pub max_token_size: usize,
+}
+
+#[derive(Args, Debug)]
+pub struct GenArgs {
+  /// Claims to sign as a JSON object, e.g. `{"sub":"1"}`. Read from a file when beginning with @, from STDIN when omitted.
+  #[clap(value_parser)]
+  pub claims: Option<String>,
+  /// Algorithm to sign with: HS256, HS384, HS512, RS256, RS384, RS512, PS256, PS384, PS512, ES256, ES384 or EdDSA.
+  #[arg(short, long, value_parser = parse_alg, default_value = "HS256")]
+  pub alg: Algorithm,
+  /// Key to sign with, in the formats of `--secret`: the HMAC secret as text, base64 (beginning with b64:) or hex (beginning with hex:), or a private key file (beginning with @).
+  #[arg(short = 'S', long, value_parser, required = true)]
+  pub secret: String,
+  /// `kid` header parameter of the token.
+  #[arg(long, value_parser)]
+  pub kid: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+  /// File with one token per line, read from STDIN when omitted.
+  #[clap(value_parser)]
+  pub file: Option<PathBuf>,
+  /// Secret to verify the tokens with, like the one of `verify`. Key files are read for every token, like `verify` does.
+  #[arg(short = 'S', long, value_parser, required = true)]
+  pub secret: String,
+  /// Allowed clock skew (seconds) when checking the `exp` and `nbf` claims.
+  #[arg(long, value_parser, default_value_t = 60)]
+  pub leeway: i64,
+  /// Check time claims against this instant instead of the system clock, as a unix timestamp or RFC 3339 date.
+  #[arg(long, value_name = "TIME", value_parser = parse_now)]
+  pub now: Option<i64>,
+  /// Verify the tokens this many times, for a longer run with few tokens.
+  #[arg(long, value_parser = clap::value_parser!(u32).range(1..), default_value_t = 1)]
+  pub rounds: u32,
+  /// Exit with 0 even when tokens failed, e.g. for a file mixing valid and expired tokens.
+  #[arg(long, value_parser, default_value_t = false)]
+  pub allow_failures: bool,
+  /// Reject tokens longer than this many bytes instead of decoding them, like the UI does.
+  #[arg(long, value_name = "BYTES", value_parser, default_value_t = DEFAULT_MAX_TOKEN_SIZE)]
+  pub max_token_size: usize,
+}
+
+/// How the decoded token and errors are printed to STDOUT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+  Text,
+  Json,
+}
+
+impl FromStr for OutputFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s {
+      "text" => Ok(OutputFormat::Text),
+      "json" => Ok(OutputFormat::Json),
+      _ => Err(format!("`{s}` is neither `text` nor `json`")),
+    }
+  }
+}
+
+impl Cli {
+  /// prints to STDOUT instead of starting the UI
+  fn is_headless(&self) -> bool {
+    self.stdout
+      || self.json
+      || self.raw_payload
+      || self.header
+      || self.header_only
+      || !self.claims.is_empty()
+      || self.path.is_some()
+      || self.output == OutputFormat::Json
+  }
+
+  fn config(&self) -> Config {
+    let mut config = Config {
+      min_lifetime: self.min_lifetime,
+      max_lifetime: self.max_lifetime,
+      max_exp_in_future: self.max_exp_in_future,
+      leeway: self.leeway,
+      strict: self.strict,
+      vim_keys: self.vim_keys,
+      scroll_step: self.scroll_step.into(),
+      page_step: self.page_step.into(),
+      web_decoder_url: self.web_decoder_url.clone(),
+      curl_url: self.curl_url.clone(),
+      require_signature: self.require_signature,
+      now: self.now,
+      audiences: self.audiences.clone(),
+      audience_mode: self.audience_mode,
+      audience_severity: self.audience_severity,
+      issuer: self.issuer.clone(),
+      issuer_severity: self.issuer_severity,
+      jti_denylist: self.jti_denylist.clone(),
+      allow_expired_cert: self.allow_expired_cert,
+      allow_no_exp: self.allow_no_exp,
+      time_claims: self.time_claims.clone(),
+      date_format: self.date_format,
+      claim_labels: self.claim_label.iter().cloned().collect(),
+      title_claim: self.title_claim.clone(),
+      max_token_size: self.max_token_size,
+      confirm_quit: self.confirm_quit,
+      batch: self.batch,
+      keys: self.key.clone(),
+      ..Config::default()
+    };
+    config
+      .min_secret_lengths
+      .extend(self.min_secret_length.iter().cloned());
+    config.weak_algs.extend(self.weak_alg.iter().cloned());
+    for alg in &self.allow_alg {
+      config.weak_algs.remove(alg);
+    }
+    config
+  }
+}
+
+fn parse_min_secret_length(value: &str) -> std::result::Result<(String, usize), String> {
+  let (alg, bytes) = value
+    .split_once('=')
+    .ok_or("expected `ALG=BYTES`, e.g. `HS256=32`")?;
+  if !["HS256", "HS384", "HS512"].contains(&alg) {
+    return Err(format!("`{alg}` is not an HMAC algorithm"));
+  }
+  let bytes = bytes
+    .parse()
+    .map_err(|_| format!("`{bytes}` is not a number of bytes"))?;
+  Ok((alg.into(), bytes))
+}
+
+fn parse_key(value: &str) -> std::result::Result<(String, String), String> {
+  match value.split_once('=') {
+    Some((label, secret)) if !label.is_empty() && !secret.is_empty() => {
+      Ok((label.into(), secret.into()))
+    }
+    _ => Err("expected `LABEL=SECRET`, e.g. `prod=@prod.pem`".into()),
+  }
+}
+
+fn parse_weak_alg(value: &str) -> std::result::Result<(String, String), String> {
+  match value.split_once('=') {
+    Some((alg, reason)) if !alg.is_empty() && !reason.is_empty() => Ok((alg.into(), reason.into())),
+    _ => Err("expected `ALG=REASON`, e.g. `HS256=not allowed here`".into()),
+  }
+}
+
+fn parse_claim_label(value: &str) -> std::result::Result<(String, String), String> {
+  match value.split_once('=') {
+    // the labels are saved comma separated in the settings
+    Some((_, label)) if label.contains(',') => Err("labels can't contain `,`".into()),
+    Some((claim, label)) if !claim.is_empty() && !label.is_empty() => {
+      Ok((claim.into(), label.into()))
+    }
+    _ => Err("expected `CLAIM=LABEL`, e.g. `x-tn=Tenant ID`".into()),
+  }
+}
+
+fn parse_claim_name(value: &str) -> std::result::Result<String, String> {
+  match value.trim() {
+    "" => Err("claim names can't be empty, e.g. `sub,aud,exp`".into()),
+    claim => Ok(claim.into()),
+  }
+}
+
+fn parse_path(value: &str) -> std::result::Result<String, String> {
+  parse_claim_path(value).map(|_| value.into())
+}
+
+fn parse_now(value: &str) -> std::result::Result<i64, String> {
+  value
+    .parse()
+    .or_else(|_| DateTime::parse_from_rfc3339(value).map(|d| d.timestamp()))
+    .map_err(|_| format!("`{value}` is neither a unix timestamp nor an RFC 3339 date"))
+}
+
+fn read_jti_denylist(path: &str) -> std::result::Result<BTreeSet<String>, String> {
+  fs::read_to_string(path)
+    .map(|content| parse_jti_denylist(&content))
+    .map_err(|e| format!("unable to read {path}: {e}"))
+}
+
+fn parse_alg(value: &str) -> std::result::Result<Algorithm, String> {
+  Algorithm::from_str(value)
+    .map_err(|_| format!("`{value}` is not an algorithm like `HS256`, `RS256` or `EdDSA`"))
 }
 
 type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
 fn main() -> Result<()> {
   panic::set_hook(Box::new(|info| {
+    log::error!("{info}");
     panic_hook(info);
   }));
 
-  // parse CLI arguments
-  let cli = Cli::parse();
+  // parse CLI arguments, remembering which were given so that they take precedence over settings
+  let matches = Cli::command().get_matches();
+  let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+  if let Some(path) = &cli.log_file {
+    if let Err(e) = init_logging(path, std::env::var("RUST_LOG").ok().as_deref()) {
+      eprintln!("{e}");
+      process::exit(1)
+    }
+  }
+  let overridden = Setting::ALL
+    .iter()
+    .flat_map(|setting| setting.args())
+    .copied()
+    .filter(|arg| matches.value_source(arg) == Some(ValueSource::CommandLine))
+    .collect::<Vec<_>>();
+
+  let mut warnings = vec![];
+  match &cli.command {
+    Some(Command::Verify(args)) => run_verify(args),
+    Some(Command::Csv(args)) => run_csv(args),
+    Some(Command::Gen(args)) => run_gen(args),
+    Some(Command::Bench(args)) => run_bench(args),
+    Some(Command::Open(args)) => match DebugFile::load(&args.file) {
+      Ok(file) => {
+        cli.token = file.token;
+        cli.secret = file.secret;
+        warnings = file
+          .warnings
+          .iter()
+          .map(|w| format!("Debug file: {w}"))
+          .collect();
+      }
+      Err(e) => {
+        eprintln!("{e}");
+        process::exit(1)
+      }
+    },
+    None => {}
+  }
 
-  if cli.tick_rate >= 1000 {
-    panic!("Tick rate must be below 1000");
+  if cli.from_clipboard {
+    match read_clipboard() {
+      Ok(content) => match extract_token(&content) {
+        Some(extracted) => cli.token = Some(extracted.token),
+        None => warnings.push("Clipboard: no JWT found".into()),
+      },
+      Err(e) => warnings.push(format!("Clipboard: {e}")),
+    }
+    // there is nothing to print without a token
+    if cli.is_headless() && cli.token.is_none() {
+      eprintln!("{}", warnings.join("\n"));
+      process::exit(1)
+    }
   }
 
-  if (cli.stdout || cli.json) && cli.token.is_some() {
-    to_stdout(cli);
+  if cli.is_headless() && cli.token.is_some() {
+    to_stdout(cli, &overridden);
   } else {
+    // the token argument is normalized like a pasted one, so that the token block shows the token
+    if let Some(input) = cli.token.take() {
+      let (token, note) = token_arg(&input);
+      cli.token = Some(token);
+      warnings.extend(note.map(|note| format!("Token: {note}")));
+    }
     // The UI must run in the "main" thread
-    start_ui(cli)?;
+    start_ui(cli, &warnings, &overridden)?;
   }
 
   Ok(())
 }
 
-fn to_stdout(cli: Cli) {
-  let mut app = App::new(cli.tick_rate, cli.token.clone(), cli.secret.clone());
+/// the profiles of the profiles file and the name of the one to use, picked with `--profile` or the
+/// default one. Exits when the picked one can't be used, a file that can't be read is only a
+/// warning otherwise
+fn cli_profiles(
+  cli: &Cli,
+  warnings: &mut Vec<String>,
+) -> (BTreeMap<String, Profile>, Option<String>) {
+  let profiles = config_dir()
+    .map(|dir| load_profiles(&dir.join(PROFILES_FILE)))
+    .unwrap_or_else(|| Ok(BTreeMap::new()));
+  let profiles = match profiles {
+    Ok(profiles) => profiles,
+    Err(e) if cli.profile.is_some() => {
+      eprintln!("{e}");
+      process::exit(1)
+    }
+    Err(e) => {
+      warnings.push(e);
+      BTreeMap::new()
+    }
+  };
+  match select_profile(&profiles, cli.profile.as_deref()) {
+    Ok(active) => {
+      let active = active.map(|(name, _)| name.clone());
+      (profiles, active)
+    }
+    Err(e) => {
+      eprintln!("{e}");
+      process::exit(1)
+    }
+  }
+}
+
+/// arguments profiles don't change, the secret also when it's from a debug file
+fn profile_overridden<'a>(cli: &Cli, overridden: &[&'a str]) -> Vec<&'a str> {
+  let mut overridden = overridden.to_vec();
+  if !cli.secret.is_empty() {
+    overridden.push("secret");
+  }
+  overridden
+}
+
+fn to_stdout(cli: Cli, overridden: &[&'static str]) {
+  let token = cli.token.as_deref().map(headless_token);
+  if cli.header_only {
+    match decode_header_only(token.as_deref().unwrap_or_default()) {
+      Ok(header) => {
+        println!("{}", serde_json::to_string_pretty(&header).unwrap());
+        process::exit(0)
+      }
+      Err(e) if cli.output == OutputFormat::Json => {
+        print_error_json(
+          VerifyFailure::from(&e.clone().into()).code(),
+          &e.to_string(),
+        );
+        process::exit(1)
+      }
+      Err(e) => {
+        eprintln!("{e}");
+        process::exit(1)
+      }
+    }
+  }
+  let mut warnings = vec![];
+  let (profiles, active) = cli_profiles(&cli, &mut warnings);
+  for warning in warnings {
+    eprintln!("warning: {warning}");
+  }
+  let profile = active.map(|name| &profiles[&name]);
+  let overridden = profile_overridden(&cli, overridden);
+  // without a secret there is nothing to verify with
+  let secret = if cli.insecure_no_verify {
+    String::new()
+  } else {
+    profile
+      .and_then(|profile| profile.secret.clone())
+      .filter(|_| !overridden.contains(&"secret"))
+      .unwrap_or_else(|| cli.secret.clone())
+  };
+  let mut app = App::new(cli.tick_rate, token.clone(), secret);
+  app.config = cli.config();
+  if let Some(profile) = profile {
+    let base = app.config.clone();
+    profile.apply(&mut app.config, &base, &overridden);
+  }
   // print decoded result to stdout
-  decode_jwt_token(&mut app, cli.no_verify);
+  decode_jwt_token(&mut app, cli.no_verify || cli.insecure_no_verify);
+  // header advisories are failures with --strict
+  let nonconforming = token
+    .filter(|_| app.config.strict)
+    .and_then(|token| check_conformance(&token, &app.config).err());
+  // tokens that couldn't be decoded have no summary and fail closed as well
+  let unverified = app
+    .data
+    .decoder
+    .summary
+    .as_ref()
+    .is_none_or(|summary| summary.is_unverified());
+  // only failures with `--audience-severity` and `--issuer-severity` `error` and denylisted `jti`,
+  // warnings are printed with the others
+  let claim_mismatch = app
+    .data
+    .decoder
+    .summary
+    .as_ref()
+    .and_then(|summary| summary.claim_failure())
+    .map(|(claim, reason)| (claim, reason.to_string()));
+  let json_output = cli.output == OutputFormat::Json;
+  if json_output {
+    if let Some(reason) = &nonconforming {
+      print_error_json(reason.code(), &reason.to_string());
+      process::exit(1);
+    }
+    if !app.data.error.is_empty() || !app.data.decoder.is_decoded() {
+      let code = app
+        .data
+        .decoder
+        .failure()
+        .map_or("invalid", |e| VerifyFailure::from(e).code());
+      print_error_json(code, &app.data.error);
+      process::exit(1);
+    }
+    // a single JSON document is printed, either the token or the error
+    if app.config.require_signature && unverified {
+      print_error_json(
+        "unverified",
+        "the signature is required but wasn't verified",
+      );
+      process::exit(1);
+    }
+    if let Some((claim, reason)) = &claim_mismatch {
+      print_error_json(&format!("{claim}_mismatch"), reason);
+      process::exit(1);
+    }
+  }
   if app.data.error.is_empty() && app.data.decoder.is_decoded() {
-    print_decoded_token(app.data.decoder.get_decoded().as_ref().unwrap(), cli.json);
+    let decoded = app.data.decoder.get_decoded().unwrap();
+    if cli.insecure_no_verify {
+      eprintln!("warning: the signature of the token was not verified (--insecure-no-verify)");
+    }
+    if let Some(line) = decoded.secret_line {
+      eprintln!("note: verified with the secret on line {line} of the secrets file");
+    }
+    if let Some(label) = &decoded.key_label {
+      eprintln!("note: verified with the key `{label}`");
+    }
+    if cli.header {
+      print_raw_header(&decoded);
+    } else if !cli.claims.is_empty() {
+      print_claims(&decoded, &cli.claims, cli.null_missing);
+    } else if let Some(path) = &cli.path {
+      if let Err(e) = print_claim_at_path(&decoded, path, cli.raw) {
+        eprintln!("{e}");
+        process::exit(1);
+      }
+    } else if cli.raw_payload {
+      print_raw_payload(&decoded);
+    } else {
+      print_decoded_token(
+        &decoded,
+        app.data.decoder.warnings(),
+        cli.json || json_output,
+      );
+    }
+  } else if cli.raw_payload || cli.header || !cli.claims.is_empty() || cli.path.is_some() {
+    // nothing goes to STDOUT, so that a pipeline doesn't mistake the error for the output
+    eprintln!("{}", app.data.error);
+    process::exit(1);
   } else {
     println!("{}", app.data.error);
   }
+  if app.config.require_signature && unverified {
+    eprintln!("INVALID (unverified): the signature is required but wasn't verified");
+    process::exit(1);
+  }
+  if let Some(reason) = nonconforming {
+    eprintln!("INVALID (strict): {reason}");
+    process::exit(1);
+  }
+  if let Some((claim, reason)) = claim_mismatch {
+    eprintln!("INVALID ({claim}): {reason}");
+    process::exit(1);
+  }
+}
+
+/// extracts the token from input like `Authorization: Bearer <token>`, other tokens in it are noted on STDERR
+fn headless_token(input: &str) -> String {
+  let (token, note) = token_arg(input);
+  if let Some(note) = note {
+    eprintln!("note: {note}");
+  }
+  token
+}
+
+/// the token in input like `Authorization: Bearer <token>`, with a note when the input held more
+/// than the token. Input without a JWT is kept as-is for the decoder to report
+fn token_arg(input: &str) -> (String, Option<String>) {
+  match extract_token(input) {
+    Some(extracted) => {
+      let note = if extracted.ignored > 0 {
+        Some(format!(
+          "decoded the first token, {} other token(s) in the input were ignored",
+          extracted.ignored
+        ))
+      } else if extracted.is_embedded_in(input) {
+        Some("decoded the token found in the input".into())
+      } else {
+        None
+      };
+      (extracted.token, note)
+    }
+    None => (input.into(), None),
+  }
+}
+
+fn run_verify(args: &VerifyArgs) -> ! {
+  let token = headless_token(&args.token);
+  let now = args.now.unwrap_or_else(|| Utc::now().timestamp());
+  let conforming = if args.strict {
+    check_conformance(&token, &Config::default())
+  } else {
+    Ok(())
+  };
+  match conforming
+    .and_then(|_| verify_decoded(&token, &args.secret, args.leeway, now, args.max_token_size))
+  {
+    Ok(decoded) => {
+      let failures = check_assertions(&decoded.payload, &args.assertions, now);
+      if !failures.is_empty() {
+        for failure in failures {
+          if args.path.is_some() {
+            eprintln!("{failure}");
+          } else {
+            println!("{failure}");
+          }
+        }
+        process::exit(1)
+      }
+      // claims are only printed from a token that passed
+      if let Some(path) = &args.path {
+        if let Err(e) = print_claim_at_path(&decoded, path, args.raw) {
+          eprintln!("{e}");
+          process::exit(1)
+        }
+      }
+      process::exit(0)
+    }
+    // STDOUT stays empty when a claim was asked for, so the reason isn't mistaken for it
+    Err(reason) if args.path.is_some() => {
+      eprintln!("{reason}");
+      process::exit(1)
+    }
+    Err(reason) => {
+      println!("{reason}");
+      process::exit(1)
+    }
+  }
+}
+
+fn run_csv(args: &CsvArgs) -> ! {
+  let input = match &args.file {
+    Some(path) => fs::read_to_string(path),
+    None => {
+      let mut input = String::new();
+      io::stdin().read_to_string(&mut input).map(|_| input)
+    }
+  };
+  match input {
+    Ok(input) => {
+      print!(
+        "{}",
+        claims_to_csv(
+          &input,
+          &args.columns,
+          args.readable_dates,
+          args.max_token_size
+        )
+      );
+      process::exit(0)
+    }
+    Err(e) => {
+      eprintln!("Unable to read tokens: {e}");
+      process::exit(1)
+    }
+  }
+}
+
+/// exits when the secret is to be read from STDIN, which the subcommand reads its `input` from
+fn reject_stdin_secret(secret: &str, input: &str) {
+  if reads_stdin(secret) {
+    eprintln!("The secret can't be read from STDIN, the {input} are read from it");
+    process::exit(1)
+  }
+}
+
+fn run_gen(args: &GenArgs) -> ! {
+  if args.claims.is_none() {
+    reject_stdin_secret(&args.secret, "claims");
+  }
+  let claims = match args.claims.as_deref() {
+    Some(file) if file.starts_with('@') => fs::read_to_string(&file[1..]),
+    Some(claims) => Ok(claims.into()),
+    None => {
+      let mut claims = String::new();
+      io::stdin().read_to_string(&mut claims).map(|_| claims)
+    }
+  };
+  let claims = claims.unwrap_or_else(|e| {
+    eprintln!("Unable to read claims: {e}");
+    process::exit(1)
+  });
+  match generate_token(&claims, args.alg, &args.secret, args.kid.as_deref()) {
+    Ok(token) => {
+      println!("{token}");
+      process::exit(0)
+    }
+    Err(e) => {
+      eprintln!("{e}");
+      process::exit(1)
+    }
+  }
+}
+
+fn run_bench(args: &BenchArgs) -> ! {
+  if args.file.is_none() {
+    reject_stdin_secret(&args.secret, "tokens");
+  }
+  let input = match &args.file {
+    Some(path) => fs::read_to_string(path),
+    None => {
+      let mut input = String::new();
+      io::stdin().read_to_string(&mut input).map(|_| input)
+    }
+  };
+  let input = input.unwrap_or_else(|e| {
+    eprintln!("Unable to read tokens: {e}");
+    process::exit(1)
+  });
+  let now = args.now.unwrap_or_else(|| Utc::now().timestamp());
+  let report = bench(
+    &input,
+    &args.secret,
+    args.leeway,
+    now,
+    args.rounds as usize,
+    args.max_token_size,
+  );
+  println!("{report}");
+  if report.total() == 0 {
+    eprintln!("No tokens to verify");
+    process::exit(1)
+  }
+  process::exit(i32::from(report.failed > 0 && !args.allow_failures))
 }
 
 /// Enable mouse capture, but don't enable capture of all the mouse movements, doing so will improve performance, and is part of the fix for the weird mouse event output bug
@@ -101,7 +939,20 @@ pub fn enable_mouse_capture() -> Result<()> {
   )
 }
 
-fn start_ui(cli: Cli) -> Result<()> {
+/// `warnings` are shown as notifications and kept in the action log. Saved settings are applied
+/// except those `overridden` on the command line
+fn start_ui(cli: Cli, warnings: &[String], overridden: &[&'static str]) -> Result<()> {
+  // remapped before the app is made, its tab titles show the keys. Read before raw mode, so that
+  // a file that can't be used is reported on STDERR
+  if let Some(path) = config_dir().map(|dir| dir.join(KEYBINDINGS_FILE)) {
+    match load_key_bindings(&path) {
+      Ok(bindings) => set_key_bindings(bindings),
+      Err(e) => {
+        eprintln!("{e}");
+        process::exit(1)
+      }
+    }
+  }
   // see https://docs.rs/crossterm/0.17.7/crossterm/terminal/#raw-mode
   enable_raw_mode()?;
   // Terminal initialization
@@ -114,10 +965,62 @@ fn start_ui(cli: Cli) -> Result<()> {
   let mut terminal = Terminal::new(backend)?;
   terminal.clear()?;
   terminal.hide_cursor()?;
-  // custom events
-  let events = event::Events::new(cli.tick_rate);
-
   let mut app = App::new(cli.tick_rate, cli.token.clone(), cli.secret.clone());
+  app.config = cli.config();
+  app.set_decoder_compact(cli.compact);
+  app.data.decoder.array_preview = cli.array_preview;
+  app.high_contrast = cli.high_contrast;
+  for warning in warnings {
+    app.notify(warning);
+  }
+  app.settings.path = config_dir().map(|dir| dir.join(SETTINGS_FILE));
+  if let Some(path) = app.settings.path.clone() {
+    for warning in load_settings(&mut app, &path, overridden) {
+      app.notify(&warning);
+    }
+  }
+  // profiles are applied over the settings, like the command line options
+  let mut warnings = vec![];
+  let (profiles, active) = cli_profiles(&cli, &mut warnings);
+  app.profiles = Profiles {
+    profiles,
+    base: app.config.clone(),
+    overridden: profile_overridden(&cli, overridden),
+    ..Profiles::default()
+  };
+  if let Some(name) = active {
+    use_profile(&mut app, &name);
+  }
+  for warning in warnings {
+    app.notify(&warning);
+  }
+  // custom events, at the tick rate of the settings
+  let events = event::Events::new(app.tick_rate);
+
+  let state_file = config_dir().map(|dir| dir.join("state.json"));
+  let mut restored = false;
+  if cli.restore_state {
+    if let Some(state) = state_file.as_deref().and_then(NavigationState::load) {
+      state.apply(&mut app);
+      restored = true;
+    }
+  }
+  // ready for a paste, unless there's a token already or the restored block is to be kept. Batch
+  // mode is always ready for the next token
+  if (cli.token.is_none() || cli.batch) && !cli.no_edit_on_start && !restored {
+    app.start_editing_token();
+  }
+  // the title is only touched where the terminal can restore it
+  let title_claim = app.config.title_claim.clone().filter(|_| {
+    supports_window_title(
+      std::env::var("TERM").ok().as_deref(),
+      io::stdout().is_terminal(),
+    )
+  });
+  let mut title = None;
+  if title_claim.is_some() {
+    write!(terminal.backend_mut(), "{PUSH_TITLE}")?;
+  }
   // main UI loop
   loop {
     // Get the size of the screen on each loop to account for resize event
@@ -131,6 +1034,14 @@ fn start_ui(cli: Cli) -> Result<()> {
     // draw the UI layout
     terminal.draw(|f| ui::draw(f, &mut app))?;
 
+    if let Some(claim) = &title_claim {
+      let current = window_title(&app.data.decoder, claim);
+      if title.as_ref() != Some(&current) {
+        execute!(terminal.backend_mut(), SetTitle(&current))?;
+        title = Some(current);
+      }
+    }
+
     // handle key events
     match events.next()? {
       event::Event::Input(key_event) => {
@@ -142,6 +1053,10 @@ fn start_ui(cli: Cli) -> Result<()> {
         }
         // handle all other keys
         handlers::handle_key_events(key, key_event, &mut app);
+        if let Some(edit) = app.pending_external_edit.take() {
+          let edited = edit_externally(&mut terminal, &events, &edit)?;
+          app.apply_external_edit(edit.block, edited);
+        }
       }
       // handle mouse events
       event::Event::MouseInput(mouse) => handlers::handle_mouse_events(mouse, &mut app),
@@ -155,12 +1070,52 @@ fn start_ui(cli: Cli) -> Result<()> {
     }
   }
 
+  if cli.restore_state {
+    if let Some(path) = state_file {
+      // failing to save navigation state shouldn't prevent a clean exit
+      let _ = NavigationState::from_app(&app).save(&path);
+    }
+  }
+
+  if title_claim.is_some() {
+    write!(terminal.backend_mut(), "{POP_TITLE}")?;
+  }
   terminal.show_cursor()?;
   shutdown(terminal)?;
+  // only now that the terminal is restored, so that the UI doesn't mangle the output
+  if let Some(output) = app.output_on_quit {
+    println!("{output}");
+  }
 
   Ok(())
 }
 
+/// suspends the UI while the editor has the terminal, the outcome of the edit is returned
+fn edit_externally(
+  terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+  events: &event::Events,
+  edit: &ExternalEdit,
+) -> Result<std::result::Result<String, String>> {
+  events.pause();
+  disable_raw_mode()?;
+  execute!(
+    terminal.backend_mut(),
+    LeaveAlternateScreen,
+    DisableMouseCapture
+  )?;
+  terminal.show_cursor()?;
+
+  let edited = edit.run(&editor_command());
+
+  enable_raw_mode()?;
+  execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+  enable_mouse_capture()?;
+  terminal.hide_cursor()?;
+  terminal.clear()?;
+  events.resume();
+  Ok(edited)
+}
+
 // shutdown the CLI and show terminal
 fn shutdown(mut terminal: Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
   disable_raw_mode()?;
@@ -174,7 +1129,7 @@ fn shutdown(mut terminal: Terminal<CrosstermBackend<Stdout>>) -> io::Result<()>
 }
 
 #[cfg(debug_assertions)]
-fn panic_hook(info: &PanicInfo<'_>) {
+fn panic_hook(info: &PanicHookInfo<'_>) {
   use backtrace::Backtrace;
   use crossterm::style::Print;
 
@@ -204,7 +1159,7 @@ fn panic_hook(info: &PanicInfo<'_>) {
 }
 
 #[cfg(not(debug_assertions))]
-fn panic_hook(info: &PanicInfo<'_>) {
+fn panic_hook(info: &PanicHookInfo<'_>) {
   use human_panic::{handle_dump, print_msg, Metadata};
 
   let meta = Metadata {
@@ -0,0 +1,79 @@
+use ratatui::{
+  layout::Rect,
+  text::{Line, Text},
+  widgets::{Clear, Paragraph},
+  Frame,
+};
+
+use super::utils::{
+  centered_rect, layout_block_with_line, style_default, style_primary, title_with_dual_style,
+};
+use crate::app::App;
+
+/// popup over the centre of `area` listing the latest actions, newest last
+pub fn draw_action_log(f: &mut Frame<'_>, app: &App, area: Rect) {
+  let area = centered_rect(area, 70, 60);
+  f.render_widget(Clear, area);
+
+  let title = title_with_dual_style(
+    " Action log ".into(),
+    "| close <esc> | clear <ctrl+d> ".into(),
+  );
+  let block = layout_block_with_line(title, app.light_theme, true);
+
+  let visible = area.height.saturating_sub(2) as usize;
+  let lines: Vec<Line<'_>> = if app.action_log.is_empty() {
+    vec![Line::styled(
+      "No actions logged yet",
+      style_default(app.light_theme),
+    )]
+  } else {
+    app
+      .action_log
+      .iter()
+      .skip(app.action_log.len().saturating_sub(visible))
+      .map(|entry| {
+        Line::styled(
+          format!("{} {}", entry.timestamp.format("%H:%M:%S"), entry.message),
+          style_primary(app.light_theme),
+        )
+      })
+      .collect()
+  };
+
+  f.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+
+  #[test]
+  fn test_draw_action_log() {
+    let mut app = App::default();
+    app.log_action("Decoded token 1a2b3c4d, verification passed");
+    app.notify("Copied payload");
+
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal
+      .draw(|f| draw_action_log(f, &app, f.size()))
+      .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| {
+      (15..85)
+        .map(|x| buffer.get(x, y).symbol())
+        .collect::<String>()
+        .trim_start_matches('│')
+        .to_string()
+    };
+    assert!(row(4).starts_with("┌ Action log | close <esc> | clear <ctrl+d> ─"));
+    // entries are prefixed with a `HH:MM:SS` timestamp
+    assert_eq!(&row(5)[2..3], ":");
+    assert!(row(5)[9..].starts_with("Decoded token 1a2b3c4d, verification passed"));
+    assert!(row(6)[9..].starts_with("Copied payload"));
+  }
+}
@@ -0,0 +1,59 @@
+use ratatui::{
+  layout::Rect,
+  text::Text,
+  widgets::{Clear, Paragraph},
+  Frame,
+};
+
+use super::utils::{centered_rect, layout_block_with_line, style_primary, title_with_dual_style};
+use crate::app::{models::ByteInspector, App};
+
+/// popup over the centre of `area` with the hexdump of the inspected bytes
+pub fn draw_byte_inspector(f: &mut Frame<'_>, app: &App, inspector: &ByteInspector, area: Rect) {
+  let area = centered_rect(area, 80, 70);
+  f.render_widget(Clear, area);
+
+  let title = title_with_dual_style(
+    format!(" Bytes of {} ", inspector.title),
+    "| close <esc> | scroll <↑↓> ".into(),
+  );
+  let block = layout_block_with_line(title, app.light_theme, true);
+
+  let text = Text::styled(inspector.dump.get_txt(), style_primary(app.light_theme));
+  f.render_widget(
+    Paragraph::new(text)
+      .block(block)
+      .scroll((inspector.dump.offset, 0)),
+    area,
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+
+  #[test]
+  fn test_draw_byte_inspector() {
+    let app = App::default();
+    let inspector = ByteInspector::new("claim name".into(), b"Jo\x07e");
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal
+      .draw(|f| draw_byte_inspector(f, &app, &inspector, f.size()))
+      .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| {
+      (10..90)
+        .map(|x| buffer.get(x, y).symbol())
+        .collect::<String>()
+        .trim_start_matches('│')
+        .to_string()
+    };
+    assert!(row(3).starts_with("┌ Bytes of claim name | close <esc> | scroll <↑↓> ─"));
+    assert!(row(4).starts_with("00000000  4a 6f 07 65"));
+    assert!(row(4).contains("|Jo.e|"));
+  }
+}
@@ -0,0 +1,69 @@
+use ratatui::{
+  layout::{Constraint, Rect},
+  widgets::{Clear, Row, Table},
+  Frame,
+};
+
+use super::{
+  utils::{
+    centered_rect, layout_block_with_line, style_highlight, style_primary, title_with_dual_style,
+  },
+  HIGHLIGHT,
+};
+use crate::app::{
+  models::{CopyTarget, StatefulTable},
+  App,
+};
+
+/// popup over the centre of `area` listing what can be copied from the decoder
+pub fn draw_copy_menu(f: &mut Frame<'_>, app: &App, menu: &StatefulTable<CopyTarget>, area: Rect) {
+  let area = centered_rect(area, 50, 50);
+  f.render_widget(Clear, area);
+
+  let title = title_with_dual_style(" Copy ".into(), "| copy <enter> | close <esc> ".into());
+  let block = layout_block_with_line(title, app.light_theme, true);
+
+  let rows = menu
+    .items
+    .iter()
+    .map(|target| Row::new(vec![target.to_string()]).style(style_primary(app.light_theme)));
+  let table = Table::new(rows, [Constraint::Percentage(100)])
+    .block(block)
+    .highlight_style(style_highlight())
+    .highlight_symbol(HIGHLIGHT);
+
+  // the selection is only read here, it's moved by the key handler
+  let mut state = menu.state.clone();
+  f.render_stateful_widget(table, area, &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+
+  #[test]
+  fn test_draw_copy_menu() {
+    let app = App::default();
+    let menu = StatefulTable::with_items(vec![
+      CopyTarget::HeaderJson,
+      CopyTarget::ClaimValue("sub".into()),
+    ]);
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal
+      .draw(|f| draw_copy_menu(f, &app, &menu, f.size()))
+      .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| {
+      (25..75)
+        .map(|x| buffer.get(x, y).symbol())
+        .collect::<String>()
+    };
+    assert!(row(5).starts_with("┌ Copy | copy <enter> | close <esc> ─"));
+    assert!(row(6).starts_with("│=> Header JSON"));
+    assert!(row(7).starts_with("│   Value of claim 'sub'"));
+  }
+}
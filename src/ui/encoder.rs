@@ -45,8 +45,8 @@ fn draw_header_block(f: &mut Frame<'_>, app: &mut App, area: Rect) {
 
   let block = get_selectable_block(
     "Header: Algorithm & Token Type",
-    *app.data.encoder.blocks.get_active_block() == ActiveBlock::EncoderHeader,
-    Some(&app.data.encoder.header.input_mode),
+    app.is_focused(ActiveBlock::EncoderHeader),
+    &app.block_actions(ActiveBlock::EncoderHeader),
     app.light_theme,
   );
 
@@ -60,8 +60,8 @@ fn draw_payload_block(f: &mut Frame<'_>, app: &mut App, area: Rect) {
 
   let block = get_selectable_block(
     "Payload: Claims",
-    *app.data.encoder.blocks.get_active_block() == ActiveBlock::EncoderPayload,
-    Some(&app.data.encoder.payload.input_mode),
+    app.is_focused(ActiveBlock::EncoderPayload),
+    &app.block_actions(ActiveBlock::EncoderPayload),
     app.light_theme,
   );
   f.render_widget(block, area);
@@ -74,8 +74,8 @@ fn draw_secret_block(f: &mut Frame<'_>, app: &mut App, area: Rect) {
 
   let block = get_selectable_block(
     "Signing Secret",
-    *app.data.encoder.blocks.get_active_block() == ActiveBlock::EncoderSecret,
-    Some(&app.data.encoder.secret.input_mode),
+    app.is_focused(ActiveBlock::EncoderSecret),
+    &app.block_actions(ActiveBlock::EncoderSecret),
     app.light_theme,
   );
 
@@ -85,7 +85,7 @@ fn draw_secret_block(f: &mut Frame<'_>, app: &mut App, area: Rect) {
     vertical_chunks_with_margin(vec![Constraint::Length(1), Constraint::Min(2)], area, 1);
 
   let mut text = Text::from(
-    "Prepend 'b64:' for base64 encoded secret. Prepend '@' for file path (.pem, .pk8, .der, .json)",
+    "Prepend 'b64:' or 'hex:' for base64 or hex encoded secret. Prepend '@' for file path (.pem, .pk8, .der, .json)",
   );
   text = text.patch_style(style_default(app.light_theme));
   let paragraph = Paragraph::new(text).block(Block::default());
@@ -100,8 +100,8 @@ fn draw_token_block(f: &mut Frame<'_>, app: &mut App, area: Rect) {
 
   let block = get_selectable_block(
     "Encoded Token",
-    *app.data.encoder.blocks.get_active_block() == ActiveBlock::EncoderToken,
-    None,
+    app.is_focused(ActiveBlock::EncoderToken),
+    &app.block_actions(ActiveBlock::EncoderToken),
     app.light_theme,
   );
 
@@ -113,6 +113,7 @@ fn draw_token_block(f: &mut Frame<'_>, app: &mut App, area: Rect) {
   let mut txt = Text::from(encoded.clone());
   txt = txt.patch_style(style_primary(app.light_theme));
 
+  app.data.encoder.encoded.set_width(chunks[0].width);
   let paragraph = Paragraph::new(txt)
     .block(Block::default())
     .wrap(Wrap { trim: false })
@@ -189,7 +190,7 @@ mod tests {
 
     let mut expected = Buffer::with_lines(vec![
       r#"┌ Header: Algorithm & Token Type (<enter> edit | ┐┌ Signing Secret ────────────────────────────────┐"#,
-      r#"│┌──────────────────────────────────────────────┐││Prepend 'b64:' for base64 encoded secret. Prepen│"#,
+      r#"│┌──────────────────────────────────────────────┐││Prepend 'b64:' or 'hex:' for base64 or hex encod│"#,
       r#"││{                                             │││┌──────────────────────────────────────────────┐│"#,
       r#"││  "alg": "HS256",                             ││││secret                                        ││"#,
       r#"││  "typ": "JWT"                                │││└──────────────────────────────────────────────┘│"#,
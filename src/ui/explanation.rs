@@ -0,0 +1,65 @@
+use ratatui::{
+  layout::Rect,
+  text::Text,
+  widgets::{Clear, Paragraph, Wrap},
+  Frame,
+};
+
+use super::utils::{centered_rect, layout_block_with_line, style_primary, title_with_dual_style};
+use crate::app::App;
+
+/// popup over the centre of `area` with the plain English summary of the decoded token
+pub fn draw_explanation(f: &mut Frame<'_>, app: &App, explanation: &str, area: Rect) {
+  let area = centered_rect(area, 60, 40);
+  f.render_widget(Clear, area);
+
+  let title = title_with_dual_style(" Explain token ".into(), "| close <esc> ".into());
+  let block = layout_block_with_line(title, app.light_theme, true);
+
+  let text = Text::styled(explanation.to_string(), style_primary(app.light_theme));
+  f.render_widget(
+    Paragraph::new(text).block(block).wrap(Wrap { trim: true }),
+    area,
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+
+  #[test]
+  fn test_draw_explanation() {
+    let app = App::default();
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal
+      .draw(|f| {
+        draw_explanation(
+          f,
+          &app,
+          "This is an HS256-signed JWT subject user-123, issued 5m ago, expires in 55m. Signature verified.",
+          f.size(),
+        )
+      })
+      .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| {
+      (20..80)
+        .map(|x| buffer.get(x, y).symbol())
+        .collect::<String>()
+    };
+    assert!(row(6).starts_with("┌ Explain token | close <esc> ─"));
+    // wrapped within the popup
+    assert_eq!(
+      row(7).trim_end_matches(['│', ' ']),
+      "│This is an HS256-signed JWT subject user-123, issued 5m"
+    );
+    assert_eq!(
+      row(8).trim_end_matches(['│', ' ']),
+      "│ago, expires in 55m. Signature verified."
+    );
+  }
+}
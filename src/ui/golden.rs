@@ -0,0 +1,82 @@
+//! Golden buffers for rendering tests. A golden is a text file in `test_data/golden` with the
+//! symbols of every row, a blank line and a row of style codes per cell, see [`style_code`].
+//! Run the tests with `UPDATE_GOLDEN=1` to write the current rendering instead of comparing it
+
+use std::{env, fs};
+
+use ratatui::{
+  buffer::{Buffer, Cell},
+  style::{Color, Modifier},
+};
+
+use super::utils::{
+  COLOR_BLUE, COLOR_CYAN, COLOR_DARK_GRAY, COLOR_GRAY, COLOR_GREEN, COLOR_GREEN_DARK,
+  COLOR_LIGHT_BLUE, COLOR_MAGENTA, COLOR_MAGENTA_DARK, COLOR_ORANGE, COLOR_ORANGE_DARK, COLOR_RED,
+  COLOR_RED_DARK, COLOR_TEAL, COLOR_WHITE, COLOR_YELLOW,
+};
+
+/// one letter per foreground color of the themes, upper case when bold. `.` is the terminal's
+/// default color and `?` any other color
+fn style_code(cell: &Cell) -> char {
+  let code = match cell.fg {
+    Color::Reset => return '.',
+    COLOR_YELLOW => 'y',
+    COLOR_WHITE => 'w',
+    COLOR_CYAN => 'c',
+    COLOR_ORANGE => 'o',
+    COLOR_GREEN => 'g',
+    COLOR_RED => 'r',
+    COLOR_MAGENTA => 'm',
+    COLOR_LIGHT_BLUE => 'l',
+    COLOR_TEAL => 't',
+    COLOR_BLUE => 'b',
+    COLOR_GREEN_DARK => 'n',
+    COLOR_RED_DARK => 'd',
+    COLOR_ORANGE_DARK => 'e',
+    COLOR_MAGENTA_DARK => 'k',
+    COLOR_GRAY => 'a',
+    COLOR_DARK_GRAY => 'x',
+    _ => return '?',
+  };
+  if cell.modifier.contains(Modifier::BOLD) {
+    code.to_ascii_uppercase()
+  } else {
+    code
+  }
+}
+
+fn to_golden(buffer: &Buffer) -> String {
+  let area = buffer.area;
+  let rows = |f: &dyn Fn(&Cell) -> String| {
+    (area.top()..area.bottom())
+      .map(|y| {
+        (area.left()..area.right())
+          .map(|x| f(buffer.get(x, y)))
+          .collect::<String>()
+      })
+      .collect::<Vec<_>>()
+      .join("\n")
+  };
+  format!(
+    "{}\n\n{}\n",
+    rows(&|cell| cell.symbol().to_string()),
+    rows(&|cell| style_code(cell).to_string())
+  )
+}
+
+/// compares the buffer against the golden `test_data/golden/<name>.txt`
+pub fn assert_golden(buffer: &Buffer, name: &str) {
+  let path = format!("./test_data/golden/{name}.txt");
+  let actual = to_golden(buffer);
+  if env::var_os("UPDATE_GOLDEN").is_some() {
+    fs::write(&path, actual).unwrap();
+    return;
+  }
+  let expected = fs::read_to_string(&path).unwrap_or_else(|e| {
+    panic!("Unable to read {path}, run with UPDATE_GOLDEN=1 to create it: {e}")
+  });
+  assert_eq!(
+    actual, expected,
+    "rendering differs from {path}, run with UPDATE_GOLDEN=1 if the change is intended"
+  );
+}
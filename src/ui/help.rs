@@ -7,11 +7,11 @@ use ratatui::{
 use super::{
   utils::{
     layout_block_with_line, style_highlight, style_primary, style_secondary, title_with_dual_style,
-    vertical_chunks,
+    vertical_chunks, with_position,
   },
   HIGHLIGHT,
 };
-use crate::app::App;
+use crate::app::{ActiveBlock, App};
 
 pub fn draw_help(f: &mut Frame<'_>, app: &mut App, area: Rect) {
   let chunks = vertical_chunks(vec![Constraint::Percentage(100)], area);
@@ -44,7 +44,10 @@ pub fn draw_help(f: &mut Frame<'_>, app: &mut App, area: Rect) {
         .style(style_secondary(app.light_theme))
         .bottom_margin(0),
     )
-    .block(layout_block_with_line(title, app.light_theme, true))
+    .block(with_position(
+      layout_block_with_line(title, app.light_theme, app.is_focused(ActiveBlock::Help)),
+      app.help_docs.position(),
+    ))
     .highlight_style(style_highlight())
     .highlight_symbol(HIGHLIGHT);
   f.render_stateful_widget(help_menu, chunks[0], &mut app.help_docs.state);
@@ -60,7 +63,10 @@ mod tests {
   };
 
   use super::*;
-  use crate::ui::utils::{COLOR_CYAN, COLOR_WHITE, COLOR_YELLOW};
+  use crate::{
+    app::RouteId,
+    ui::utils::{COLOR_CYAN, COLOR_WHITE, COLOR_YELLOW},
+  };
 
   #[test]
   fn test_draw_help() {
@@ -71,10 +77,14 @@ mod tests {
       .draw(|f| {
         let size = f.size();
         let mut app = App::default();
+        app.push_navigation_stack(RouteId::Help, ActiveBlock::Help);
         draw_help(f, &mut app, size);
       })
       .unwrap();
 
+    // the position of the selection in all the keybindings
+    let position = format!(" 1/{} ", App::default().help_docs.items.len());
+    let last_line = format!("└{}{position}┘", "─".repeat(108 - position.len()));
     let mut expected = Buffer::with_lines(vec![
         "┌ Help | close <esc> ────────────────────────────────────────────────────────────────────────────────────────┐",
         "│   Key                                               Action                                            Conte│",
@@ -82,7 +92,7 @@ mod tests {
         "│   <Esc>                                             Close child page/Go back/Stop editing             Gener│",
         "│   <?>                                               Help page                                         Gener│",
         "│   <Ctrl+r>                                          Refresh UI                                        Gener│",
-        "└────────────────────────────────────────────────────────────────────────────────────────────────────────────┘",
+        &last_line,
       ]);
     // set row styles
     // First row heading style
@@ -0,0 +1,52 @@
+use ratatui::{
+  layout::{Constraint, Rect},
+  widgets::{Clear, Row, Table},
+  Frame,
+};
+
+use super::{
+  utils::{
+    centered_rect, layout_block_with_line, style_highlight, style_primary, title_with_dual_style,
+  },
+  HIGHLIGHT,
+};
+use crate::app::{models::StatefulTable, App};
+
+/// popup over the centre of `area` listing the profiles, with the issuer and audiences they expect
+pub fn draw_profiles(f: &mut Frame<'_>, app: &App, menu: &StatefulTable<String>, area: Rect) {
+  let area = centered_rect(area, 50, 50);
+  f.render_widget(Clear, area);
+
+  let title = title_with_dual_style(" Profiles ".into(), "| use <enter> | close <esc> ".into());
+  let block = layout_block_with_line(title, app.light_theme, true);
+
+  let rows = menu.items.iter().map(|name| {
+    let name_cell = if app.profiles.active.as_ref() == Some(name) {
+      format!("{name} (in use)")
+    } else {
+      name.clone()
+    };
+    let profile = &app.profiles.profiles[name];
+    let expected = profile
+      .issuer
+      .iter()
+      .map(|issuer| format!("iss {issuer}"))
+      .chain(
+        (!profile.audiences.is_empty()).then(|| format!("aud {}", profile.audiences.join(", "))),
+      )
+      .collect::<Vec<_>>()
+      .join(", ");
+    Row::new(vec![name_cell, expected]).style(style_primary(app.light_theme))
+  });
+  let table = Table::new(
+    rows,
+    [Constraint::Percentage(40), Constraint::Percentage(60)],
+  )
+  .block(block)
+  .highlight_style(style_highlight())
+  .highlight_symbol(HIGHLIGHT);
+
+  // the selection is only read here, it's moved by the key handler
+  let mut state = menu.state.clone();
+  f.render_stateful_widget(table, area, &mut state);
+}
@@ -0,0 +1,52 @@
+use ratatui::{
+  layout::Rect,
+  text::{Line, Text},
+  widgets::{Clear, Paragraph, Wrap},
+  Frame,
+};
+
+use super::utils::{centered_rect, layout_block_with_line, style_warning, title_with_dual_style};
+use crate::app::App;
+
+/// asks to confirm quitting while a secret is entered, as it's never saved
+pub fn draw_quit_confirmation(f: &mut Frame<'_>, app: &App, area: Rect) {
+  let area = centered_rect(area, 50, 20);
+  f.render_widget(Clear, area);
+
+  let title = title_with_dual_style(" Quit ".into(), "| quit <y> | cancel <any key> ".into());
+  let block = layout_block_with_line(title, app.light_theme, true);
+  let text = Text::from(vec![Line::styled(
+    "Quit? unsaved secret will be lost [y/N]",
+    style_warning(app.light_theme),
+  )]);
+  f.render_widget(
+    Paragraph::new(text).block(block).wrap(Wrap { trim: false }),
+    area,
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+
+  #[test]
+  fn test_draw_quit_confirmation() {
+    let app = App::default();
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal
+      .draw(|f| draw_quit_confirmation(f, &app, f.size()))
+      .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| {
+      (25..75)
+        .map(|x| buffer.get(x, y).symbol())
+        .collect::<String>()
+    };
+    assert!(row(8).starts_with("┌ Quit | quit <y> | cancel <any key> ─"));
+    assert!(row(9).starts_with("│Quit? unsaved secret will be lost [y/N]"));
+  }
+}
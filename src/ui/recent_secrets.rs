@@ -0,0 +1,37 @@
+use ratatui::{
+  layout::{Constraint, Rect},
+  widgets::{Clear, Row, Table},
+  Frame,
+};
+
+use super::{
+  utils::{
+    centered_rect, layout_block_with_line, style_highlight, style_primary, title_with_dual_style,
+  },
+  HIGHLIGHT,
+};
+use crate::app::{models::StatefulTable, utils::strip_leading_symbol, App};
+
+/// popup over the centre of `area` listing the key files used recently
+pub fn draw_recent_secrets(f: &mut Frame<'_>, app: &App, menu: &StatefulTable<String>, area: Rect) {
+  let area = centered_rect(area, 50, 50);
+  f.render_widget(Clear, area);
+
+  let title = title_with_dual_style(
+    " Recent keys ".into(),
+    "| use <enter> | close <esc> ".into(),
+  );
+  let block = layout_block_with_line(title, app.light_theme, true);
+
+  let rows = menu.items.iter().map(|secret| {
+    Row::new(vec![strip_leading_symbol(secret)]).style(style_primary(app.light_theme))
+  });
+  let table = Table::new(rows, [Constraint::Percentage(100)])
+    .block(block)
+    .highlight_style(style_highlight())
+    .highlight_symbol(HIGHLIGHT);
+
+  // the selection is only read here, it's moved by the key handler
+  let mut state = menu.state.clone();
+  f.render_stateful_widget(table, area, &mut state);
+}
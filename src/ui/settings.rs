@@ -0,0 +1,125 @@
+use ratatui::{
+  layout::{Constraint, Rect},
+  text::Line,
+  widgets::{Paragraph, Row, Table},
+  Frame,
+};
+
+use super::{
+  utils::{
+    get_input_style, layout_block_with_line, render_input_widget, style_highlight, style_primary,
+    style_secondary, title_with_dual_style, vertical_chunks, with_position,
+  },
+  HIGHLIGHT,
+};
+use crate::app::{ActiveBlock, App, InputMode};
+
+pub fn draw_settings(f: &mut Frame<'_>, app: &mut App, area: Rect) {
+  let editing = app.settings.input.input_mode == InputMode::Editing;
+  let chunks = if editing {
+    vertical_chunks(vec![Constraint::Min(0), Constraint::Length(3)], area)
+  } else {
+    vertical_chunks(vec![Constraint::Min(0)], area)
+  };
+
+  let rows = app
+    .settings
+    .table
+    .items
+    .iter()
+    .map(|setting| {
+      let note = if setting.needs_restart() {
+        "restart needed"
+      } else {
+        ""
+      };
+      Row::new(vec![setting.to_string(), setting.value(app), note.into()])
+        .style(style_primary(app.light_theme))
+    })
+    .collect::<Vec<_>>();
+
+  let title = title_with_dual_style(" Settings ".into(), "| toggle/edit <enter> ".into());
+  let table = Table::new(
+    rows,
+    [
+      Constraint::Length(30),
+      Constraint::Min(0),
+      Constraint::Length(16),
+    ],
+  )
+  .header(Row::new(vec!["Setting", "Value", "Note"]).style(style_secondary(app.light_theme)))
+  .block(with_position(
+    layout_block_with_line(
+      title,
+      app.light_theme,
+      app.is_focused(ActiveBlock::Settings) && !editing,
+    ),
+    app.settings.table.position(),
+  ))
+  .highlight_style(style_highlight())
+  .highlight_symbol(HIGHLIGHT);
+  f.render_stateful_widget(table, chunks[0], &mut app.settings.table.state);
+
+  if editing {
+    let input = &app.settings.input;
+    render_input_widget(f, chunks[1], input, app.light_theme);
+    if chunks[1].width >= 4 {
+      let setting = app.settings.table.get_selected_item_copy();
+      let title = format!(
+        " {} (<enter> apply | <esc> cancel | <ctrl+d> clear) ",
+        setting.map(|s| s.to_string()).unwrap_or_default()
+      );
+      f.render_widget(
+        Paragraph::new(Line::styled(
+          title,
+          get_input_style(&input.input_mode, app.light_theme),
+        )),
+        Rect::new(chunks[1].x + 1, chunks[1].y, chunks[1].width - 2, 1),
+      );
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+  use crate::app::settings::select_setting;
+
+  #[test]
+  fn test_draw_settings() {
+    let mut app = App::new(250, None, "".into());
+    app.route_settings();
+    let backend = TestBackend::new(80, 17);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal
+      .draw(|f| draw_settings(f, &mut app, f.size()))
+      .unwrap();
+
+    let row = |terminal: &Terminal<TestBackend>, y: u16| {
+      let buffer = terminal.backend().buffer();
+      (0..80)
+        .map(|x| buffer.get(x, y).symbol())
+        .collect::<String>()
+    };
+    assert!(row(&terminal, 0).starts_with("┌ Settings | toggle/edit <enter> ─"));
+    assert!(row(&terminal, 1).starts_with("│   Setting                        Value"));
+    assert!(row(&terminal, 2).starts_with("│=> Light theme                    off"));
+    assert!(row(&terminal, 3).starts_with("│   High contrast                  off"));
+    assert!(row(&terminal, 6).contains("Claim labels  "));
+    assert!(row(&terminal, 10).contains("Weak algs                      HS1, RS1, none"));
+    assert!(row(&terminal, 15).contains("Tick rate (ms)                 250"));
+    assert!(row(&terminal, 15).trim_end().ends_with("restart needed  │"));
+    assert!(row(&terminal, 16).ends_with(" 1/14 ┘"));
+
+    // the value is edited below the table
+    app.settings.table.state.select(Some(7));
+    select_setting(&mut app);
+    terminal
+      .draw(|f| draw_settings(f, &mut app, f.size()))
+      .unwrap();
+    assert!(row(&terminal, 14).starts_with("┌ Leeway (seconds) (<enter> apply | <esc> cancel"));
+    assert!(row(&terminal, 15).starts_with("│60 "));
+  }
+}
@@ -0,0 +1,74 @@
+use ratatui::{
+  layout::{Constraint, Rect},
+  text::{Line, Text},
+  widgets::{Clear, Paragraph},
+  Frame,
+};
+
+use super::utils::{
+  centered_rect, layout_block_with_line, style_error, style_primary, style_success,
+  title_with_dual_style, vertical_chunks_with_margin, with_symbol,
+};
+use crate::app::{models::TokenInspector, App};
+
+/// popup over the centre of `area` with the claim value decoded as a token, its verdict first
+pub fn draw_token_inspector(f: &mut Frame<'_>, app: &App, inspector: &TokenInspector, area: Rect) {
+  let area = centered_rect(area, 80, 70);
+  f.render_widget(Clear, area);
+
+  let title = title_with_dual_style(
+    format!(" Token in {} ", inspector.title),
+    "| close <esc> | scroll <↑↓> ".into(),
+  );
+  f.render_widget(layout_block_with_line(title, app.light_theme, true), area);
+
+  let chunks =
+    vertical_chunks_with_margin(vec![Constraint::Length(2), Constraint::Min(0)], area, 1);
+  let verdict = match &inspector.verdict {
+    Ok(verdict) => Line::styled(with_symbol("✓", verdict), style_success(app.light_theme)),
+    Err(verdict) => Line::styled(with_symbol("✗", verdict), style_error(app.light_theme)),
+  };
+  f.render_widget(Paragraph::new(verdict), chunks[0]);
+
+  let text = Text::styled(inspector.text.get_txt(), style_primary(app.light_theme));
+  f.render_widget(
+    Paragraph::new(text).scroll((inspector.text.offset, 0)),
+    chunks[1],
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+  use crate::app::models::ScrollableTxt;
+
+  #[test]
+  fn test_draw_token_inspector() {
+    let app = App::default();
+    let inspector = TokenInspector {
+      title: "claim id_token".into(),
+      verdict: Err("Signature not verified: InvalidSignature".into()),
+      text: ScrollableTxt::new("Header\n{}".into()),
+    };
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal
+      .draw(|f| draw_token_inspector(f, &app, &inspector, f.size()))
+      .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| {
+      (10..90)
+        .map(|x| buffer.get(x, y).symbol())
+        .collect::<String>()
+        .trim_start_matches('│')
+        .to_string()
+    };
+    assert!(row(3).starts_with("┌ Token in claim id_token | close <esc> | scroll <↑↓> ─"));
+    assert!(row(4).starts_with("Signature not verified: InvalidSignature"));
+    assert!(row(6).starts_with("Header"));
+    assert!(row(7).starts_with("{}"));
+  }
+}
@@ -1,14 +1,22 @@
-use std::{collections::BTreeMap, rc::Rc};
+use std::{cell::Cell, collections::BTreeMap, env, rc::Rc};
 
 use ratatui::{
-  layout::{Constraint, Direction, Layout, Rect},
+  layout::{Alignment, Constraint, Direction, Layout, Rect},
   style::{Color, Modifier, Style},
   text::{Line, Span},
-  widgets::{Block, Borders, Paragraph, Wrap},
+  widgets::{
+    block::{Position, Title},
+    Block, Borders, Paragraph, Wrap,
+  },
   Frame,
 };
 
-use crate::app::{InputMode, TextInput};
+use tui_input::Input;
+
+use crate::app::{
+  key_binding::{hint, BlockAction},
+  InputMode, TextInput,
+};
 
 // Utils
 
@@ -30,13 +38,38 @@ pub const COLOR_BLUE: Color = Color::Rgb(0, 82, 163);
 pub const COLOR_GREEN_DARK: Color = Color::Rgb(20, 97, 73);
 pub const COLOR_RED_DARK: Color = Color::Rgb(173, 25, 20);
 pub const COLOR_ORANGE_DARK: Color = Color::Rgb(184, 49, 15);
+// high-contrast colors, on black for the dark theme and on white for the light one
+pub const COLOR_BLACK: Color = Color::Rgb(0, 0, 0);
+pub const COLOR_HC_YELLOW: Color = Color::Rgb(255, 255, 0);
+pub const COLOR_HC_GREEN: Color = Color::Rgb(0, 255, 0);
+pub const COLOR_HC_RED: Color = Color::Rgb(255, 90, 90);
+pub const COLOR_HC_CYAN: Color = Color::Rgb(0, 255, 255);
+pub const COLOR_HC_BLUE_DARK: Color = Color::Rgb(0, 0, 160);
+pub const COLOR_HC_GREEN_DARK: Color = Color::Rgb(0, 90, 0);
+pub const COLOR_HC_RED_DARK: Color = Color::Rgb(170, 0, 0);
+pub const COLOR_HC_BROWN: Color = Color::Rgb(120, 50, 0);
+pub const COLOR_HC_PURPLE: Color = Color::Rgb(90, 0, 140);
+
+thread_local! {
+  // set from the app on each draw, so that the style helpers don't all need to be told
+  static HIGH_CONTRAST: Cell<bool> = const { Cell::new(false) };
+}
+
+/// switches the styles to the high-contrast palette and the verdicts to carry symbols
+pub fn set_high_contrast(on: bool) {
+  HIGH_CONTRAST.with(|high_contrast| high_contrast.set(on));
+}
+
+pub fn is_high_contrast() -> bool {
+  HIGH_CONTRAST.with(Cell::get)
+}
 
 #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Styles {
   Default,
   Header,
   Logo,
-  Failure,
+  Error,
   Warning,
   Success,
   Primary,
@@ -46,12 +79,15 @@ pub enum Styles {
 }
 
 pub fn theme_styles(light: bool) -> BTreeMap<Styles, Style> {
+  if is_high_contrast() {
+    return high_contrast_styles(light);
+  }
   if light {
     BTreeMap::from([
       (Styles::Default, Style::default().fg(COLOR_GRAY)),
       (Styles::Header, Style::default().fg(COLOR_DARK_GRAY)),
       (Styles::Logo, Style::default().fg(COLOR_GREEN_DARK)),
-      (Styles::Failure, Style::default().fg(COLOR_RED_DARK)),
+      (Styles::Error, Style::default().fg(COLOR_RED_DARK)),
       (Styles::Warning, Style::default().fg(COLOR_ORANGE_DARK)),
       (Styles::Success, Style::default().fg(COLOR_GREEN_DARK)),
       (Styles::Primary, Style::default().fg(COLOR_BLUE)),
@@ -67,7 +103,7 @@ pub fn theme_styles(light: bool) -> BTreeMap<Styles, Style> {
       (Styles::Default, Style::default().fg(COLOR_WHITE)),
       (Styles::Header, Style::default().fg(COLOR_DARK_GRAY)),
       (Styles::Logo, Style::default().fg(COLOR_GREEN)),
-      (Styles::Failure, Style::default().fg(COLOR_RED)),
+      (Styles::Error, Style::default().fg(COLOR_RED)),
       (Styles::Warning, Style::default().fg(COLOR_ORANGE)),
       (Styles::Success, Style::default().fg(COLOR_GREEN)),
       (Styles::Primary, Style::default().fg(COLOR_CYAN)),
@@ -81,20 +117,96 @@ pub fn theme_styles(light: bool) -> BTreeMap<Styles, Style> {
   }
 }
 
+/// black on white or white on black, with the verdicts in bold so they don't rely on the hue
+fn high_contrast_styles(light: bool) -> BTreeMap<Styles, Style> {
+  let bold = |color| Style::default().fg(color).add_modifier(Modifier::BOLD);
+  let (fg, bg, error, warning, success, primary, secondary) = if light {
+    (
+      COLOR_BLACK,
+      COLOR_WHITE,
+      COLOR_HC_RED_DARK,
+      COLOR_HC_BROWN,
+      COLOR_HC_GREEN_DARK,
+      COLOR_HC_BLUE_DARK,
+      COLOR_HC_PURPLE,
+    )
+  } else {
+    (
+      COLOR_WHITE,
+      COLOR_BLACK,
+      COLOR_HC_RED,
+      COLOR_HC_YELLOW,
+      COLOR_HC_GREEN,
+      COLOR_HC_CYAN,
+      COLOR_HC_YELLOW,
+    )
+  };
+  BTreeMap::from([
+    (Styles::Default, Style::default().fg(fg)),
+    (Styles::Header, Style::default().fg(COLOR_BLACK)),
+    (Styles::Logo, Style::default().fg(fg)),
+    (Styles::Error, bold(error)),
+    (Styles::Warning, bold(warning)),
+    (Styles::Success, bold(success)),
+    (Styles::Primary, Style::default().fg(primary)),
+    (Styles::Secondary, bold(secondary)),
+    (Styles::Help, Style::default().fg(fg)),
+    (Styles::Background, Style::default().bg(bg).fg(fg)),
+  ])
+}
+
+/// `text` after the symbol of a pass, fail or warning like `✓`, `✗` or `!`, when the colors
+/// aren't to be relied on alone
+pub fn with_symbol(symbol: &str, text: &str) -> String {
+  if is_high_contrast() {
+    format!("{symbol} {text}")
+  } else {
+    text.into()
+  }
+}
+
 pub fn style_header_text(light: bool) -> Style {
   *theme_styles(light).get(&Styles::Header).unwrap()
 }
 
 pub fn style_header() -> Style {
-  Style::default().bg(COLOR_MAGENTA)
+  if is_high_contrast() {
+    Style::default().bg(COLOR_WHITE)
+  } else {
+    Style::default().bg(COLOR_MAGENTA)
+  }
 }
 
 pub fn style_default(light: bool) -> Style {
   *theme_styles(light).get(&Styles::Default).unwrap()
 }
 
-pub fn style_failure(light: bool) -> Style {
-  *theme_styles(light).get(&Styles::Failure).unwrap()
+// errors, warnings and verdicts share these, so that they look the same across the tabs
+pub fn style_error(light: bool) -> Style {
+  *theme_styles(light).get(&Styles::Error).unwrap()
+}
+
+pub fn style_warning(light: bool) -> Style {
+  *theme_styles(light).get(&Styles::Warning).unwrap()
+}
+
+pub fn style_success(light: bool) -> Style {
+  *theme_styles(light).get(&Styles::Success).unwrap()
+}
+
+/// style of a pass or fail marker like `✓` and `✗ expired`. The marker's text tells the verdict,
+/// so it's left plain when `NO_COLOR` is set
+pub fn style_verdict(valid: bool, light: bool) -> Style {
+  match env::var_os("NO_COLOR") {
+    Some(no_color) if !no_color.is_empty() => Style::default(),
+    _ if valid => style_success(light),
+    _ => style_error(light),
+  }
+}
+
+/// style of the claims a new token changed, until the highlight fades
+pub fn style_changed(light: bool) -> Style {
+  style_warning(light).add_modifier(Modifier::BOLD)
 }
 
 pub fn style_primary(light: bool) -> Style {
@@ -162,6 +274,35 @@ pub fn vertical_chunks_with_margin(
     .split(size)
 }
 
+/// `percent_x` by `percent_y` of `area`, centred in it
+pub fn centered_rect(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+  let rows = vertical_chunks(
+    vec![
+      Constraint::Percentage((100 - percent_y) / 2),
+      Constraint::Percentage(percent_y),
+      Constraint::Percentage((100 - percent_y) / 2),
+    ],
+    area,
+  );
+  horizontal_chunks(
+    vec![
+      Constraint::Percentage((100 - percent_x) / 2),
+      Constraint::Percentage(percent_x),
+      Constraint::Percentage((100 - percent_x) / 2),
+    ],
+    rows[1],
+  )[1]
+}
+
+/// shows `position`, like `3/27`, at the right of the bottom border
+pub fn with_position(block: Block<'_>, position: String) -> Block<'_> {
+  block.title(
+    Title::from(format!(" {position} "))
+      .position(Position::Bottom)
+      .alignment(Alignment::Right),
+  )
+}
+
 pub fn layout_block_with_line(title: Line<'_>, light: bool, is_active: bool) -> Block<'_> {
   let style = if is_active {
     style_secondary(light)
@@ -188,18 +329,39 @@ pub fn render_input_widget(
   text_input: &TextInput,
   light_theme: bool,
 ) {
-  let width = chunk.width.max(3) - 3;
+  let text = Line::from(text_input.input.value());
+  render_styled_input_widget(f, chunk, text_input, text, light_theme);
+}
+
+/// [`render_input_widget`] showing `text`, the styled value of the input, like a token with its
+/// segments colored
+pub fn render_styled_input_widget(
+  f: &mut Frame<'_>,
+  chunk: Rect,
+  text_input: &TextInput,
+  text: Line<'_>,
+  light_theme: bool,
+) {
+  // borders and a line of text need at least 3x3 cells
+  if chunk.width < 3 || chunk.height < 3 {
+    return;
+  }
+  let width = chunk.width - 3;
   // keep 2 for borders and 1 for cursor
-  let scroll = text_input.input.visual_scroll(width as usize);
-  let input = Paragraph::new(text_input.input.value())
-    .wrap(Wrap { trim: false })
+  let (scroll, cursor) = input_scroll_and_cursor(&text_input.input, width as usize);
+  let mut input = Paragraph::new(text)
     .style(get_input_style(&text_input.input_mode, light_theme))
-    .scroll((0, scroll as u16))
     .block(
       Block::default()
         .borders(Borders::ALL)
         .style(get_input_style(&text_input.input_mode, light_theme)),
     );
+  // horizontal scroll is ignored by wrapped paragraphs, so only wrap inputs spanning multiple lines
+  if chunk.height > 3 {
+    input = input.wrap(Wrap { trim: false });
+  } else {
+    input = input.scroll((0, scroll as u16));
+  }
 
   f.render_widget(input, chunk);
 
@@ -212,7 +374,7 @@ pub fn render_input_widget(
       // Make the cursor visible and ask tui-rs to put it at the specified coordinates after rendering
       f.set_cursor(
         // Put cursor past the end of the input text
-        chunk.x + ((text_input.input.visual_cursor()).max(scroll) - scroll) as u16 + 1,
+        chunk.x + cursor as u16 + 1,
         // Move one line down, from the border to the input line
         chunk.y + 1,
       )
@@ -220,14 +382,38 @@ pub fn render_input_widget(
   }
 }
 
-pub fn get_hint(input_mode: &InputMode, is_active: bool) -> &str {
-  if is_active {
-    match input_mode {
-      InputMode::Normal => "(<enter> edit | <c> copy) ",
-      InputMode::Editing => "(<esc> stop editing | <ctrl+d> clear) ",
+/// Returns the horizontal scroll offset and the cursor column of an input shown in `width` columns.
+/// Both are in display columns rather than chars, so wide glyphs (CJK, emoji) take up two cells
+pub fn input_scroll_and_cursor(input: &Input, width: usize) -> (usize, usize) {
+  let scroll = input.visual_scroll(width);
+  (scroll, input.visual_cursor().saturating_sub(scroll))
+}
+
+/// cuts `text` to `width` display columns, ending it with `…` when it's cut
+pub fn truncate_with_ellipsis(text: &str, width: usize) -> String {
+  if Span::raw(text).width() <= width {
+    return text.into();
+  }
+  let mut truncated = String::new();
+  let mut used = 0;
+  for c in text.chars() {
+    let char_width = Span::raw(c.to_string()).width();
+    if used + char_width + 1 > width {
+      break;
     }
+    used += char_width;
+    truncated.push(c);
+  }
+  truncated.push('…');
+  truncated
+}
+
+/// the hint for the actions of a block, only shown while it's focused
+pub fn get_hint(actions: &[BlockAction], is_active: bool) -> String {
+  if is_active {
+    hint(actions)
   } else {
-    ""
+    String::new()
   }
 }
 
@@ -241,22 +427,122 @@ pub fn get_input_style(input_mode: &InputMode, light: bool) -> Style {
 pub fn get_selectable_block(
   title: &str,
   is_active: bool,
-  input_mode: Option<&InputMode>,
+  actions: &[BlockAction],
   light_theme: bool,
 ) -> Block<'static> {
-  //   let is_active = *active_block == block;
-  let title_hint = if let Some(im) = input_mode {
-    get_hint(im, is_active)
-  } else if is_active {
-    "(<c> copy) "
-  } else {
-    ""
-  };
+  let title_hint = get_hint(actions, is_active);
 
   let block = layout_block_with_line(
-    title_with_dual_style(format!(" {} ", title), title_hint.into()),
+    title_with_dual_style(format!(" {} ", title), title_hint),
     light_theme,
     is_active,
   );
   block
 }
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+
+  #[test]
+  fn test_truncate_with_ellipsis() {
+    assert_eq!(truncate_with_ellipsis("sub", 5), "sub");
+    assert_eq!(truncate_with_ellipsis("preferred_username", 8), "preferr…");
+    // wide chars take two columns
+    assert_eq!(truncate_with_ellipsis("名前名前", 6), "名前…");
+  }
+
+  #[test]
+  fn test_status_styles() {
+    for light in [false, true] {
+      let colors = [
+        style_error(light),
+        style_warning(light),
+        style_success(light),
+      ]
+      .map(|style| style.fg.unwrap());
+      assert!(colors[0] != colors[1] && colors[1] != colors[2] && colors[0] != colors[2]);
+    }
+    assert_eq!(style_error(false).fg, Some(COLOR_RED));
+    assert_eq!(style_error(true).fg, Some(COLOR_RED_DARK));
+    assert_eq!(style_warning(true).fg, Some(COLOR_ORANGE_DARK));
+    assert_eq!(style_success(true).fg, Some(COLOR_GREEN_DARK));
+  }
+
+  #[test]
+  fn test_high_contrast_styles() {
+    set_high_contrast(true);
+    assert_eq!(style_main_background(false).bg, Some(COLOR_BLACK));
+    assert_eq!(style_main_background(true).bg, Some(COLOR_WHITE));
+    assert_eq!(style_default(true).fg, Some(COLOR_BLACK));
+    for light in [false, true] {
+      let colors = [
+        style_error(light),
+        style_warning(light),
+        style_success(light),
+      ]
+      .map(|style| style.fg.unwrap());
+      assert!(colors[0] != colors[1] && colors[1] != colors[2] && colors[0] != colors[2]);
+      assert!(style_error(light).add_modifier.contains(Modifier::BOLD));
+    }
+    assert_eq!(with_symbol("✓", "VALID"), "✓ VALID");
+
+    set_high_contrast(false);
+    assert_eq!(style_error(false).fg, Some(COLOR_RED));
+    assert_eq!(with_symbol("✓", "VALID"), "VALID");
+  }
+
+  #[test]
+  fn test_input_scroll_and_cursor_with_wide_glyphs() {
+    // '秘' and '密' are two columns wide each
+    let input = Input::new("ab秘密".into());
+    assert_eq!(input_scroll_and_cursor(&input, 10), (0, 6));
+    // scrolling by a single column would cut 'a' only, the cursor stays right after '密'
+    assert_eq!(input_scroll_and_cursor(&input, 5), (1, 5));
+    // a wide glyph is never cut in half
+    let input = Input::new("秘密ab".into());
+    assert_eq!(input_scroll_and_cursor(&input, 5), (2, 4));
+  }
+
+  #[test]
+  fn test_render_input_widget_skips_degenerate_area() {
+    let text_input = TextInput {
+      input: Input::new("secret".into()),
+      input_mode: InputMode::Editing,
+    };
+    let mut terminal = Terminal::new(TestBackend::new(10, 10)).unwrap();
+
+    for area in [
+      Rect::new(0, 0, 0, 0),
+      Rect::new(0, 0, 10, 2),
+      Rect::new(0, 0, 2, 10),
+    ] {
+      let frame = terminal
+        .draw(|f| render_input_widget(f, area, &text_input, false))
+        .unwrap();
+      assert!(frame.buffer.content.iter().all(|cell| cell.symbol() == " "));
+    }
+  }
+
+  #[test]
+  fn test_render_input_widget_cursor_after_wide_glyph() {
+    let text_input = TextInput {
+      input: Input::new("abcd秘".into()),
+      input_mode: InputMode::Editing,
+    };
+    let mut terminal = Terminal::new(TestBackend::new(8, 3)).unwrap();
+    terminal
+      .draw(|f| render_input_widget(f, f.size(), &text_input, false))
+      .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = (0..8)
+      .map(|x| buffer.get(x, 1).symbol())
+      .collect::<String>();
+    assert_eq!(row, "│bcd秘  │");
+    // the cursor sits right after the wide glyph, not on its second half
+    assert_eq!(terminal.get_cursor().unwrap(), (6, 1));
+  }
+}
@@ -0,0 +1,71 @@
+use ratatui::{
+  layout::Rect,
+  text::{Line, Text},
+  widgets::{Clear, Paragraph, Wrap},
+  Frame,
+};
+
+use super::utils::{
+  centered_rect, layout_block_with_line, style_default, style_primary, style_warning,
+  title_with_dual_style,
+};
+use crate::app::App;
+
+/// asks to confirm opening `url`, which holds the token, in the browser
+pub fn draw_web_decoder_confirmation(f: &mut Frame<'_>, app: &App, url: &str, area: Rect) {
+  let area = centered_rect(area, 70, 40);
+  f.render_widget(Clear, area);
+
+  let title = title_with_dual_style(
+    " Open in web decoder ".into(),
+    "| open <y> | cancel <any key> ".into(),
+  );
+  let block = layout_block_with_line(title, app.light_theme, true);
+
+  let text = Text::from(vec![
+    Line::styled(
+      "Open the token in the browser with this URL? The secret is not sent.",
+      style_default(app.light_theme),
+    ),
+    Line::default(),
+    Line::styled(url.to_string(), style_primary(app.light_theme)),
+    Line::default(),
+    Line::styled(
+      "Anyone with the token can use it until it expires.",
+      style_warning(app.light_theme),
+    ),
+  ]);
+  f.render_widget(
+    Paragraph::new(text).block(block).wrap(Wrap { trim: false }),
+    area,
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+
+  #[test]
+  fn test_draw_web_decoder_confirmation() {
+    let app = App::default();
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal
+      .draw(|f| draw_web_decoder_confirmation(f, &app, "https://jwt.example/#a.b.c", f.size()))
+      .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| {
+      (15..85)
+        .map(|x| buffer.get(x, y).symbol())
+        .collect::<String>()
+        .trim_start_matches('│')
+        .to_string()
+    };
+    assert!(row(6).starts_with("┌ Open in web decoder | open <y> | cancel <any key> ─"));
+    assert!(row(7).starts_with("Open the token in the browser with this URL?"));
+    assert!(row(9).starts_with("https://jwt.example/#a.b.c"));
+  }
+}